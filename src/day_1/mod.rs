@@ -1,6 +1,8 @@
 use std::fs;
 
-use crate::shared::{get_chars, DayResult};
+use crate::shared::{
+    checked_add, checked_mul, checked_sum, get_chars, DayResult, Location, NumberError,
+};
 
 pub fn solve() -> Result<DayResult, String> {
     let input = match fs::read_to_string("src/day_1/input.txt") {
@@ -11,7 +13,10 @@ pub fn solve() -> Result<DayResult, String> {
     let part_1 = solve_part_1(&input)?;
     let part_2 = solve_part_2(&input)?;
 
-    return Ok(DayResult { part_1: part_1, part_2: part_2});
+    Ok(DayResult {
+        part_1: part_1.into(),
+        part_2: part_2.into(),
+    })
 }
 
 fn solve_part_1(input: &str) -> Result<usize, String> {
@@ -28,15 +33,24 @@ fn solve_part_1(input: &str) -> Result<usize, String> {
             return find_last_int_part_1(row).unwrap();
         })
         .collect();
-    let combined_ints: Vec<usize> = first_ints
+    let combined_ints: Vec<(usize, Location)> = first_ints
         .iter()
         .enumerate()
-        .map(|item| return item.1 * 10 + last_ints[item.0])
-        .collect();
-    result = combined_ints.iter().sum();
+        .map(|item| combine_ints(*item.1, last_ints[item.0], item.0))
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.to_string())?;
+    result = checked_sum(combined_ints).map_err(|err| err.to_string())?;
     return Ok(result);
 }
 
+/// helper function to make the calibration value on the line at `index`, counted from 0, from its first and last
+/// digits
+fn combine_ints(first: usize, last: usize, index: usize) -> Result<(usize, Location), NumberError> {
+    let location = Location::Line(index + 1);
+    let value = checked_add(checked_mul(first, 10, &location)?, last, &location)?;
+    Ok((value, location))
+}
+
 fn find_first_int_part_1(input: &str) -> Result<usize, String> {
     let chars: Vec<char> = get_chars(input);
     let numeric_chars = chars
@@ -115,12 +129,13 @@ fn solve_part_2(input: &str) -> Result<usize, String> {
         })
         .cloned()
         .collect();
-    let combined_ints: Vec<usize> = first_ints
+    let combined_ints: Vec<(usize, Location)> = first_ints
         .iter()
         .enumerate()
-        .map(|item| return item.1 * 10 + last_ints[item.0])
-        .collect();
-    result = combined_ints.iter().sum();
+        .map(|item| combine_ints(*item.1, last_ints[item.0], item.0))
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.to_string())?;
+    result = checked_sum(combined_ints).map_err(|err| err.to_string())?;
     return Ok(result);
 }
 
@@ -399,6 +414,19 @@ mod tests {
             assert_eq!(result, Ok(3))
         }
     }
+    mod test_combine_ints {
+        #[test]
+        fn names_the_line_that_overflows() {
+            use super::combine_ints;
+            use crate::shared::Location;
+
+            assert_eq!(combine_ints(4, 2, 0), Ok((42, Location::Line(1))));
+            assert_eq!(
+                combine_ints(usize::MAX, 2, 6).unwrap_err().to_string(),
+                "Overflow multiplying at line 7"
+            );
+        }
+    }
     mod test_find_last_int {
         #[test]
         fn successfully_returns_an_int() {
@@ -559,8 +587,6 @@ mod tests {
             let test = "7pqrstsixteen";
             let result = find_all_ints_part_2(test);
             assert_eq!(result.unwrap(), &[7, 6]);
-
-            
         }
     }
 }
@@ -0,0 +1,282 @@
+use crate::shared::{parse_number, Location, NumberError};
+use std::io::{self, BufRead, Read};
+use thiserror::Error;
+
+/// a game borrowed straight from its line of the log, checked when it is made so that reading its rounds
+/// afterwards cannot fail, and never allocating
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameView<'a> {
+    pub id: usize,
+    /// the line of the log the game was read from, counted from 1
+    pub line: usize,
+    /// everything after `Game <id>:`
+    rounds: &'a str,
+}
+
+/// one `<count> <colour>` entry of a round
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Draw<'a> {
+    /// numbered from 0
+    pub round: usize,
+    pub count: usize,
+    pub colour: &'a str,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum GameParseError {
+    #[error("Expected the line to start with `Game <id>:`")]
+    MissingGamePrefix,
+
+    #[error("Invalid game id `{0}`")]
+    InvalidId(String),
+
+    #[error("Expected `<count> <colour>`, found `{0}`")]
+    InvalidDraw(String),
+
+    /// an id or count too large for a `usize`
+    #[error(transparent)]
+    Number(#[from] NumberError),
+
+    #[error("Error reading line {line}: {message}")]
+    Read { line: usize, message: String },
+
+    /// returned by an action passed to `for_each_game` to stop reading early
+    #[error("Stopped before the end of the log")]
+    Stopped,
+}
+
+/// helper function to split one `<count> <colour>` entry
+fn parse_draw<'a>(draw: &'a str, location: &Location) -> Result<(usize, &'a str), GameParseError> {
+    let invalid = || GameParseError::InvalidDraw(draw.trim().to_string());
+    let (count, colour) = draw.trim().split_once(' ').ok_or_else(invalid)?;
+    let colour = colour.trim();
+    if colour.is_empty() || colour.contains(char::is_whitespace) {
+        return Err(invalid());
+    }
+    match parse_number(count, location.clone()) {
+        Ok(count) => Ok((count, colour)),
+        Err(err @ NumberError::TooLarge { .. }) => Err(err.into()),
+        Err(_) => Err(invalid()),
+    }
+}
+
+impl<'a> GameView<'a> {
+    /// a game read from line `line_number` of a log, counted from 1
+    pub fn parse(line: &'a str, line_number: usize) -> Result<Self, GameParseError> {
+        let location = Location::Line(line_number);
+        let (prefix, rounds) = line
+            .split_once(':')
+            .ok_or(GameParseError::MissingGamePrefix)?;
+        let id = prefix
+            .trim()
+            .strip_prefix("Game ")
+            .ok_or(GameParseError::MissingGamePrefix)?;
+        let id = match parse_number(id.trim(), location.clone()) {
+            Ok(id) => id,
+            Err(err @ NumberError::TooLarge { .. }) => return Err(err.into()),
+            Err(_) => return Err(GameParseError::InvalidId(id.to_string())),
+        };
+
+        for draw in rounds.split([';', ',']) {
+            parse_draw(draw, &location)?;
+        }
+        Ok(Self {
+            id,
+            line: line_number,
+            rounds,
+        })
+    }
+
+    /// every draw of every round, in the order written. `parse` has already checked every draw,
+    /// so an error here only comes from a view whose rounds were not read by `parse`
+    pub fn draws(&self) -> impl Iterator<Item = Result<Draw<'a>, GameParseError>> + 'a {
+        let location = Location::Line(self.line);
+        self.rounds
+            .split(';')
+            .enumerate()
+            .flat_map(move |(round, draws)| {
+                let location = location.clone();
+                draws.split(',').map(move |draw| {
+                    let (count, colour) = parse_draw(draw, &location)?;
+                    Ok(Draw {
+                        round,
+                        count,
+                        colour,
+                    })
+                })
+            })
+    }
+
+    pub fn round_count(&self) -> usize {
+        self.rounds.split(';').count()
+    }
+}
+
+/// reads games one line at a time from `reader`, handing each to `action` before reading the next,
+/// so only one line is ever held in memory. returns how many games were read
+pub fn for_each_game<R, F>(mut reader: R, mut action: F) -> Result<usize, GameParseError>
+where
+    R: BufRead,
+    F: FnMut(GameView<'_>) -> Result<(), GameParseError>,
+{
+    let mut line = String::new();
+    let mut games = 0;
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        line_number += 1;
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|err| GameParseError::Read {
+                line: line_number,
+                message: err.to_string(),
+            })?;
+        if read == 0 {
+            return Ok(games);
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        action(GameView::parse(line.trim_end(), line_number)?)?;
+        games += 1;
+    }
+}
+
+/// a made-up game log, written out a line at a time as it is read so that it never exists in memory all at once.
+/// the same `games` always gives the same log
+pub struct GeneratedLog {
+    next_id: usize,
+    games: usize,
+    state: u64,
+    line: Vec<u8>,
+    offset: usize,
+}
+
+impl GeneratedLog {
+    pub fn new(games: usize) -> Self {
+        Self {
+            next_id: 1,
+            games,
+            state: 0x9e37_79b9_7f4a_7c15,
+            line: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    /// helper function for a xorshift number below `limit`
+    fn next_below(&mut self, limit: u64) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state >> 32) % limit
+    }
+
+    fn next_line(&mut self) -> Vec<u8> {
+        const COLOURS: [&str; 3] = ["red", "green", "blue"];
+        let rounds = (0..1 + self.next_below(6))
+            .map(|_| {
+                let first_colour = self.next_below(3) as usize;
+                let draws = 1 + self.next_below(3) as usize;
+                (0..draws)
+                    .map(|offset| {
+                        let count = 1 + self.next_below(20);
+                        format!("{} {}", count, COLOURS[(first_colour + offset) % 3])
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect::<Vec<_>>();
+        format!("Game {}: {}\n", self.next_id, rounds.join("; ")).into_bytes()
+    }
+}
+
+impl Read for GeneratedLog {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        if self.offset == self.line.len() {
+            if self.next_id > self.games {
+                return Ok(0);
+            }
+            self.line = self.next_line();
+            self.offset = 0;
+            self.next_id += 1;
+        }
+        let length = buffer.len().min(self.line.len() - self.offset);
+        buffer[..length].copy_from_slice(&self.line[self.offset..self.offset + length]);
+        self.offset += length;
+        Ok(length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{for_each_game, Draw, GameParseError, GameView, GeneratedLog};
+    use crate::shared::{Location, NumberError};
+
+    #[test]
+    fn borrows_draws_from_the_line() {
+        let line = "Game 12: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+
+        let game = GameView::parse(line, 1).unwrap();
+
+        assert_eq!(game.id, 12);
+        assert_eq!(game.round_count(), 3);
+        let draws: Vec<Draw> = game.draws().collect::<Result<_, _>>().unwrap();
+        assert_eq!(draws.len(), 6);
+        assert_eq!(
+            draws[3],
+            Draw {
+                round: 1,
+                count: 2,
+                colour: "green"
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_games() {
+        assert_eq!(
+            GameView::parse("3 blue, 4 red", 1),
+            Err(GameParseError::MissingGamePrefix)
+        );
+        assert_eq!(
+            GameView::parse("Game x: 3 blue", 1),
+            Err(GameParseError::InvalidId(String::from("x")))
+        );
+        assert_eq!(
+            GameView::parse("Game 1: 3 blue; 4 red green", 1),
+            Err(GameParseError::InvalidDraw(String::from("4 red green")))
+        );
+        assert_eq!(
+            GameView::parse("Game 1: 3 blue;", 1),
+            Err(GameParseError::InvalidDraw(String::new()))
+        );
+        assert_eq!(
+            GameView::parse("Game 2: 99999999999999999999 blue", 4),
+            Err(GameParseError::Number(NumberError::TooLarge {
+                text: String::from("99999999999999999999"),
+                location: Location::Line(4)
+            }))
+        );
+    }
+
+    #[test]
+    fn streams_large_logs() {
+        use std::io::BufReader;
+
+        let games = 250_000;
+        let reader = BufReader::new(GeneratedLog::new(games));
+
+        let mut rounds = 0;
+        let mut highest_id = 0;
+        let read = for_each_game(reader, |game| {
+            rounds += game.round_count();
+            highest_id = game.id;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(read, games);
+        assert_eq!(highest_id, games);
+        assert!(rounds >= games);
+    }
+}
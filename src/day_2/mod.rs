@@ -1,8 +1,14 @@
-use std::{collections::HashMap, fs};
+use std::{collections::BTreeMap, fs, io::Read, time::Instant};
 extern crate regex;
 use regex::Regex;
 
-use crate::shared::DayResult;
+use crate::shared::{Answer, DayResult};
+
+mod game_parser;
+mod report;
+
+use game_parser::{for_each_game, GameParseError, GameView, GeneratedLog};
+pub use report::Report;
 
 pub fn solve() -> Result<DayResult, String> {
     let input = match fs::read_to_string("src/day_2/input.txt") {
@@ -10,34 +16,242 @@ pub fn solve() -> Result<DayResult, String> {
         Err(err) => return Err(format!("Error in reading file: {}", err)),
     };
 
-    let part_1 = solve_part_1(&input)?;
-    let part_2 = solve_part_2(&input)?;
-
-    return Ok(DayResult { part_1: part_1, part_2: part_2});
+    let (result, warnings) = solve_parts_with(&input, &BagRules::default())?;
+    print_warnings(&warnings);
+    Ok(result)
 }
 
+/// the cubes shown in one round, by colour
+type Round = BTreeMap<String, usize>;
+
 struct Game {
     id: usize,
-    red: Vec<usize>,
-    green: Vec<usize>,
-    blue: Vec<usize>,
+    /// in the order written
+    rounds: Vec<Round>,
+}
+
+impl Game {
+    fn from_view(view: &GameView) -> Result<Game, GameParseError> {
+        let mut rounds: Vec<Round> = vec![Round::new(); view.round_count()];
+        for draw in view.draws() {
+            let draw = draw?;
+            add_draw(&mut rounds[draw.round], draw.colour, draw.count);
+        }
+        Ok(Game {
+            id: view.id,
+            rounds,
+        })
+    }
+
+    /// the most cubes of `colour` shown in any one round, which is also the fewest the bag could hold
+    fn max_count(&self, colour: &str) -> usize {
+        self.rounds
+            .iter()
+            .filter_map(|round| round.get(colour))
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// every colour shown in the game, by the round it is first seen in
+    fn colours(&self) -> Vec<&str> {
+        let mut colours: Vec<&str> = vec![];
+        for colour in self.rounds.iter().flat_map(|round| round.keys()) {
+            if !colours.contains(&colour.as_str()) {
+                colours.push(colour);
+            }
+        }
+        colours
+    }
+}
+
+/// helper function to record a draw in its round. a colour drawn twice in one round counts at its largest,
+/// as it does for the bag limits
+fn add_draw(round: &mut Round, colour: &str, count: usize) {
+    match round.get_mut(colour) {
+        Some(existing) => *existing = (*existing).max(count),
+        None => {
+            round.insert(colour.to_string(), count);
+        }
+    }
+}
+
+/// helper function to print the warnings a lenient bag collected while loading the games, once each
+fn print_warnings(warnings: &[String]) {
+    for warning in warnings.iter() {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
+/// what to do with a game that shows a colour the bag limits do not mention
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Strictness {
+    /// refuse to solve
+    Strict,
+    /// warn, and treat the bag as holding none of that colour
+    Lenient,
+}
+
+/// how many cubes of each colour are in the bag
+#[derive(Debug, Clone, PartialEq)]
+pub struct BagRules {
+    /// in the order given, so reports list colours the same way
+    pub limits: Vec<(String, usize)>,
+    pub strictness: Strictness,
+}
+
+impl Default for BagRules {
+    /// the puzzle's bag: 12 red, 13 green and 14 blue cubes
+    fn default() -> Self {
+        BagRules {
+            limits: vec![
+                (String::from("red"), 12),
+                (String::from("green"), 13),
+                (String::from("blue"), 14),
+            ],
+            strictness: Strictness::Lenient,
+        }
+    }
 }
 
-fn load_game(game_string: &str) -> Result<Game, String> {
+impl BagRules {
+    /// reads limits written like a round of the game, e.g. `12 red, 13 green, 14 blue`
+    pub fn parse_limits(input: &str, strictness: Strictness) -> Result<BagRules, String> {
+        let mut limits: Vec<(String, usize)> = vec![];
+        for limit in input.split(',').map(|limit| limit.trim()) {
+            let (count, colour) = limit
+                .split_once(' ')
+                .ok_or(format!("Expected `<count> <colour>`, found `{}`", limit))?;
+            let count = match count.parse::<usize>() {
+                Ok(result) => result,
+                Err(err) => return Err(format!("Parse error in `{}`: {}", limit, err)),
+            };
+            let colour = colour.trim().to_string();
+            if limits.iter().any(|(existing, _)| *existing == colour) {
+                return Err(format!("Colour `{}` given more than once", colour));
+            }
+            limits.push((colour, count));
+        }
+        Ok(BagRules { limits, strictness })
+    }
+
+    fn limit(&self, colour: &str) -> Option<usize> {
+        self.limits
+            .iter()
+            .find(|(limit_colour, _)| limit_colour == colour)
+            .map(|(_, count)| *count)
+    }
+
+    /// checks every colour in `game` is one the bag knows about:
+    /// an error when strict, otherwise a warning added to `warnings`
+    fn check_colours(&self, game: &Game, warnings: &mut Vec<String>) -> Result<(), String> {
+        for colour in game.colours() {
+            self.check_colour(game.id, colour, warnings)?;
+        }
+        Ok(())
+    }
+
+    /// the same check as `check_colours`, straight from the line in one pass.
+    /// only unknown colours are remembered, so a game of known colours allocates nothing
+    fn check_view_colours(
+        &self,
+        view: &GameView,
+        warnings: &mut Vec<String>,
+    ) -> Result<(), String> {
+        let mut unknown: Vec<&str> = vec![];
+        for draw in view.draws() {
+            let draw = draw.map_err(|err| err.to_string())?;
+            if self.limit(draw.colour).is_none() && !unknown.contains(&draw.colour) {
+                self.check_colour(view.id, draw.colour, warnings)?;
+                unknown.push(draw.colour);
+            }
+        }
+        Ok(())
+    }
+
+    /// helper function for a single colour of game `id`
+    fn check_colour(
+        &self,
+        id: usize,
+        colour: &str,
+        warnings: &mut Vec<String>,
+    ) -> Result<(), String> {
+        if self.limit(colour).is_none() {
+            let message = format!("Game {} has unknown colour `{}`", id, colour);
+            match self.strictness {
+                Strictness::Strict => return Err(message),
+                Strictness::Lenient => warnings.push(message),
+            }
+        }
+        Ok(())
+    }
+
+    /// a game is possible if no round shows more cubes of a colour than the bag holds
+    fn is_possible(&self, game: &Game) -> bool {
+        game.colours()
+            .iter()
+            .all(|colour| game.max_count(colour) <= self.limit(colour).unwrap_or(0))
+    }
+
+    /// the product of the fewest cubes of each of the bag's colours needed to play the game
+    fn power(&self, game: &Game) -> Answer {
+        self.limits
+            .iter()
+            .map(|(colour, _)| Answer::from(game.max_count(colour)))
+            .product()
+    }
+
+    /// `is_possible` straight from the line, without building a `Game`
+    fn is_view_possible(&self, view: &GameView) -> Result<bool, GameParseError> {
+        for draw in view.draws() {
+            let draw = draw?;
+            if draw.count > self.limit(draw.colour).unwrap_or(0) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// `power` straight from the line, keeping the maxima for the bag's colours in `maxima` so it can be reused
+    fn view_power(
+        &self,
+        view: &GameView,
+        maxima: &mut Vec<usize>,
+    ) -> Result<Answer, GameParseError> {
+        maxima.clear();
+        maxima.resize(self.limits.len(), 0);
+        for draw in view.draws() {
+            let draw = draw?;
+            if let Some(index) = self
+                .limits
+                .iter()
+                .position(|(colour, _)| colour == draw.colour)
+            {
+                maxima[index] = maxima[index].max(draw.count);
+            }
+        }
+        Ok(maxima
+            .iter()
+            .map(|maximum| Answer::from(*maximum))
+            .product())
+    }
+}
 
+/// the original parser, kept as a reference for tests and benchmarks
+fn load_game_with_regex(game_string: &str) -> Result<Game, String> {
     let re_game = match Regex::new(r"Game (\d+):") {
         Ok(result) => result,
-        Err(err) => return Err(format!("Regex error: {}", err))
+        Err(err) => return Err(format!("Regex error: {}", err)),
     };
 
     let re_round = match Regex::new(r"(\d+ \w+(?:, )?)+") {
         Ok(result) => result,
-        Err(err) => return Err(format!("Regex error: {}", err))
+        Err(err) => return Err(format!("Regex error: {}", err)),
     };
 
     let re_colour = match Regex::new(r"(\d+) (\w+)") {
         Ok(result) => result,
-        Err(err) => return Err(format!("Regex error: {}", err))
+        Err(err) => return Err(format!("Regex error: {}", err)),
     };
 
     if let Some(game_cap) = re_game.captures(game_string) {
@@ -48,70 +262,209 @@ fn load_game(game_string: &str) -> Result<Game, String> {
 
         let mut rounds = vec![];
         for round_cap in re_round.captures_iter(game_string) {
-            rounds.push(round_cap[0].to_string());
-        }
-
-        let num_rounds = rounds.len();
-        let mut red = vec![0; num_rounds];
-        let mut green = vec![0; num_rounds];
-        let mut blue = vec![0; num_rounds];
+            let mut counts = Round::new();
 
-        for (round_idx, round_str) in rounds.iter().enumerate() {
-            let mut counts: HashMap<String, usize> = HashMap::new();
-
-            for colour_cap in re_colour.captures_iter(&round_str) {
+            for colour_cap in re_colour.captures_iter(&round_cap[0]) {
                 let count = match colour_cap[1].parse::<usize>() {
                     Ok(result) => result,
-                    Err(err) => return Err(format!("Parse error: {}", err))
+                    Err(err) => return Err(format!("Parse error: {}", err)),
                 };
-                let colour = colour_cap[2].to_string();
-                counts.insert(colour, count);
-            }
-
-            if let Some(&count) = counts.get("red") {
-                red[round_idx] = count;
-            }
-            if let Some(&count) = counts.get("green") {
-                green[round_idx] = count;
-            }
-            if let Some(&count) = counts.get("blue") {
-                blue[round_idx] = count;
+                add_draw(&mut counts, &colour_cap[2], count);
             }
+            rounds.push(counts);
         }
-        return Ok(Game { id: game_id, red, green, blue });
+        return Ok(Game {
+            id: game_id,
+            rounds,
+        });
     } else {
         return Err(String::from("No game found"));
     }
 }
 
-fn solve_part_1(input: &str) -> Result<usize, String> {
-    let games: Vec<Game> = input.lines().map(|line| {
-        return load_game(line).expect(&format!("Error reading game: {}", line));
-    })
-    .collect();
-    let games_r = games.into_iter()
-    .filter(|game| game.red.iter().all(|cubes| cubes <= &12)).collect::<Vec<_>>();
-    let games_rg = games_r.into_iter()
-    .filter(|game| game.green.iter().all(|cubes| cubes <= &13)).collect::<Vec<_>>();
-    let games_rgb = games_rg.into_iter()
-    .filter(|game| game.blue.iter().all(|cubes| cubes <= &14)).collect::<Vec<_>>();
-    let result = games_rgb.iter().fold(0, |count, game| count + game.id);
-    return Ok(result);
-}
-
-fn solve_part_2(input: &str) -> Result<usize, String> {
-    let games: Vec<Game> = input.lines().map(|line| {
-        return load_game(line).expect(&format!("Error reading game: {}", line));
-    })
-    .collect();
-    let powers = games.iter().map(|game| {
-        let red_max = game.red.iter().max().unwrap();
-        let green_max = game.green.iter().max().unwrap();
-        let blue_max = game.blue.iter().max().unwrap();
-        return red_max * green_max * blue_max;
-    }).collect::<Vec<_>>();
-    let result = powers.iter().sum();
-    return Ok(result);
+/// loads every game, checking its colours against the bag, along with any warnings about unknown colours
+fn load_games(input: &str, rules: &BagRules) -> Result<(Vec<Game>, Vec<String>), String> {
+    let mut games = vec![];
+    let mut warnings = vec![];
+    for (index, line) in input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+    {
+        let game = match GameView::parse(line, index + 1).and_then(|view| Game::from_view(&view)) {
+            Ok(result) => result,
+            Err(err) => return Err(format!("Error reading game `{}`: {}", line, err)),
+        };
+        rules.check_colours(&game, &mut warnings)?;
+        games.push(game);
+    }
+    Ok((games, warnings))
+}
+
+/// runs `action` on every game in `input` as it is read, without building a `Game` for any of them,
+/// adding any warnings about unknown colours to `warnings`
+fn stream_games<R, F>(
+    input: R,
+    rules: &BagRules,
+    warnings: &mut Vec<String>,
+    mut action: F,
+) -> Result<(), String>
+where
+    R: std::io::BufRead,
+    F: FnMut(&GameView) -> Result<(), String>,
+{
+    let mut failure = None;
+    let result = for_each_game(input, |view| {
+        let outcome = rules
+            .check_view_colours(&view, warnings)
+            .and_then(|_| action(&view));
+        match outcome {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                failure = Some(err);
+                Err(game_parser::GameParseError::Stopped)
+            }
+        }
+    });
+    match (result, failure) {
+        (_, Some(err)) => Err(err),
+        (Err(err), None) => Err(format!("Error reading games: {}", err)),
+        (Ok(_), None) => Ok(()),
+    }
+}
+
+/// solves both parts with a different bag
+pub fn solve_with(rules: &BagRules) -> Result<DayResult, String> {
+    let input = match fs::read_to_string("src/day_2/input.txt") {
+        Ok(result) => result,
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
+    };
+
+    let (result, warnings) = solve_parts_with(&input, rules)?;
+    print_warnings(&warnings);
+    Ok(result)
+}
+
+/// reports on every game against a bag, along with the tightest bag for the `top_k` games that need the fewest cubes
+pub fn analyse(rules: &BagRules, top_k: usize) -> Result<Report, String> {
+    let input = match fs::read_to_string("src/day_2/input.txt") {
+        Ok(result) => result,
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
+    };
+
+    let (games, warnings) = load_games(&input, rules)?;
+    print_warnings(&warnings);
+    report::build_report(&games, rules, top_k).map_err(|err| err.to_string())
+}
+
+/// both parts in a single pass over the games, along with any warnings about unknown colours
+fn solve_parts_with(input: &str, rules: &BagRules) -> Result<(DayResult, Vec<String>), String> {
+    let mut part_1 = Answer::from(0);
+    let mut part_2 = Answer::from(0);
+    let mut maxima = vec![];
+    let mut warnings = vec![];
+    stream_games(input.as_bytes(), rules, &mut warnings, |view| {
+        if rules
+            .is_view_possible(view)
+            .map_err(|err| err.to_string())?
+        {
+            part_1 += Answer::from(view.id);
+        }
+        part_2 += rules
+            .view_power(view, &mut maxima)
+            .map_err(|err| err.to_string())?;
+        Ok(())
+    })?;
+    Ok((DayResult { part_1, part_2 }, warnings))
+}
+
+#[cfg(test)]
+fn solve_part_1(input: &str) -> Result<Answer, String> {
+    solve_part_1_with(input, &BagRules::default())
+}
+
+#[cfg(test)]
+fn solve_part_1_with(input: &str, rules: &BagRules) -> Result<Answer, String> {
+    Ok(solve_parts_with(input, rules)?.0.part_1)
+}
+
+#[cfg(test)]
+fn solve_part_2(input: &str) -> Result<Answer, String> {
+    solve_part_2_with(input, &BagRules::default())
+}
+
+#[cfg(test)]
+fn solve_part_2_with(input: &str, rules: &BagRules) -> Result<Answer, String> {
+    Ok(solve_parts_with(input, rules)?.0.part_2)
+}
+
+/// times both parts on a generated log of `games` games, with the original regex parser and with `GameView`
+pub fn benchmark_parsers(games: usize) -> Result<String, String> {
+    let mut input = String::new();
+    if let Err(err) = GeneratedLog::new(games).read_to_string(&mut input) {
+        return Err(format!("Error generating games: {}", err));
+    }
+    let rules = BagRules::default();
+
+    let start = Instant::now();
+    let mut regex_answers = (Answer::from(0), Answer::from(0));
+    for line in input.lines() {
+        let game = load_game_with_regex(line)?;
+        if rules.is_possible(&game) {
+            regex_answers.0 += Answer::from(game.id);
+        }
+        regex_answers.1 += rules.power(&game);
+    }
+    let regex_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut view_answers = (Answer::from(0), Answer::from(0));
+    let mut maxima = vec![];
+    let mut warnings = vec![];
+    stream_games(input.as_bytes(), &rules, &mut warnings, |view| {
+        if rules
+            .is_view_possible(view)
+            .map_err(|err| err.to_string())?
+        {
+            view_answers.0 += Answer::from(view.id);
+        }
+        view_answers.1 += rules
+            .view_power(view, &mut maxima)
+            .map_err(|err| err.to_string())?;
+        Ok(())
+    })?;
+    let view_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut streamed_games = 0;
+    stream_games(
+        std::io::BufReader::new(GeneratedLog::new(games)),
+        &rules,
+        &mut warnings,
+        |_| {
+            streamed_games += 1;
+            Ok(())
+        },
+    )?;
+    let generated_time = start.elapsed();
+
+    if regex_answers != view_answers {
+        return Err(format!(
+            "Parsers disagree: regex gave ({}, {}), streaming gave ({}, {})",
+            regex_answers.0, regex_answers.1, view_answers.0, view_answers.1
+        ));
+    }
+    Ok(format!(
+        "{} games ({} bytes), both parsers give ({}, {})\n  regex: {:?}\n  streaming: {:?}\n  streaming from the generator, never held in memory: {:?} for {} games",
+        games,
+        input.len(),
+        view_answers.0,
+        view_answers.1,
+        regex_time,
+        view_time,
+        generated_time,
+        streamed_games
+    ))
 }
 
 #[cfg(test)]
@@ -145,4 +498,136 @@ mod tests {
             assert_eq!(actual_output, expected_output);
         }
     }
-}
\ No newline at end of file
+
+    mod parsers {
+        use super::{
+            load_game_with_regex, solve_part_1, solve_part_2, Answer, BagRules, Game, GameView,
+        };
+        use std::fs;
+
+        #[test]
+        fn game_view_matches_the_regex_parser() {
+            let input = fs::read_to_string("src/day_2/input.txt").expect("File read errored");
+
+            for line in input.lines() {
+                let expected = load_game_with_regex(line).unwrap();
+                let actual = Game::from_view(&GameView::parse(line, 1).unwrap()).unwrap();
+
+                assert_eq!(actual.id, expected.id);
+                assert_eq!(actual.rounds, expected.rounds);
+            }
+        }
+
+        #[test]
+        fn streaming_gives_the_same_answers() {
+            let input = fs::read_to_string("src/day_2/input.txt").expect("File read errored");
+            let rules = BagRules::default();
+            let games: Vec<Game> = input
+                .lines()
+                .map(|line| load_game_with_regex(line).unwrap())
+                .collect();
+
+            let part_1: usize = games
+                .iter()
+                .filter(|game| rules.is_possible(game))
+                .map(|game| game.id)
+                .sum();
+            let part_2: Answer = games.iter().map(|game| rules.power(game)).sum();
+
+            assert_eq!(solve_part_1(&input), Ok(Answer::from(part_1)));
+            assert_eq!(solve_part_2(&input), Ok(part_2));
+        }
+    }
+
+    mod bag_rules {
+        use super::{
+            load_games, solve_part_1_with, solve_part_2_with, solve_parts_with, Answer, BagRules,
+            Strictness,
+        };
+
+        const GAMES: &str = "Game 1: 3 blue, 4 red; 1 red, 2 purple\nGame 2: 1 blue, 2 green; 3 green, 4 blue, 1 red";
+
+        #[test]
+        fn parses_limits_at_runtime() {
+            let rules =
+                BagRules::parse_limits("5 red, 2 purple, 9 blue", Strictness::Strict).unwrap();
+
+            assert_eq!(
+                rules.limits,
+                vec![
+                    (String::from("red"), 5),
+                    (String::from("purple"), 2),
+                    (String::from("blue"), 9),
+                ]
+            );
+            assert!(BagRules::parse_limits("5 red, 6 red", Strictness::Strict).is_err());
+            assert!(BagRules::parse_limits("red 5", Strictness::Strict).is_err());
+        }
+
+        #[test]
+        fn counts_any_colour() {
+            let rules =
+                BagRules::parse_limits("4 red, 2 purple, 3 blue, 3 green", Strictness::Strict)
+                    .unwrap();
+
+            // game 2 shows 4 blue, which is too many
+            assert_eq!(solve_part_1_with(GAMES, &rules), Ok(Answer::from(1)));
+            // 4 red * 2 purple * 3 blue * 0 green, then 1 red * 0 purple * 4 blue * 3 green
+            assert_eq!(solve_part_2_with(GAMES, &rules), Ok(Answer::from(0)));
+        }
+
+        #[test]
+        fn unknown_colours_follow_the_strictness_setting() {
+            let strict = BagRules {
+                strictness: Strictness::Strict,
+                ..BagRules::default()
+            };
+            let lenient = BagRules {
+                strictness: Strictness::Lenient,
+                ..BagRules::default()
+            };
+
+            assert_eq!(
+                solve_part_1_with(GAMES, &strict),
+                Err(String::from("Game 1 has unknown colour `purple`"))
+            );
+            // the bag holds no purple cubes, so game 1 is impossible
+            assert_eq!(solve_part_1_with(GAMES, &lenient), Ok(Answer::from(2)));
+            // no green in game 1, then 1 red * 3 green * 4 blue
+            assert_eq!(solve_part_2_with(GAMES, &lenient), Ok(Answer::from(12)));
+        }
+
+        #[test]
+        fn warns_once_per_unknown_colour_for_both_parts() {
+            let lenient = BagRules {
+                strictness: Strictness::Lenient,
+                ..BagRules::default()
+            };
+            let games = "Game 1: 2 purple; 3 purple, 1 pink\nGame 2: 1 red";
+
+            let (_, warnings) = solve_parts_with(games, &lenient).unwrap();
+            assert_eq!(
+                warnings,
+                vec![
+                    String::from("Game 1 has unknown colour `purple`"),
+                    String::from("Game 1 has unknown colour `pink`"),
+                ]
+            );
+            let (_, warnings) = load_games(games, &lenient).unwrap();
+            assert_eq!(warnings.len(), 2);
+        }
+
+        #[test]
+        fn powers_can_exceed_a_usize() {
+            let rules = BagRules::default();
+            let games =
+                "Game 1: 4294967296 red, 4294967296 green, 2 blue\nGame 2: 1 red, 1 green, 1 blue";
+
+            // 2^32 * 2^32 * 2 + 1
+            assert_eq!(
+                solve_part_2_with(games, &rules).unwrap().to_string(),
+                "36893488147419103233"
+            );
+        }
+    }
+}
@@ -0,0 +1,435 @@
+use super::{BagRules, Game};
+use crate::shared::{checked_sum, Answer, Location, NumberError};
+use std::{collections::BTreeMap, fmt};
+
+/// everything worth knowing about one game, measured against a bag
+#[derive(Debug, PartialEq)]
+pub struct GameReport {
+    pub id: usize,
+    /// the fewest cubes of each colour the game could have been played with
+    pub minimal_bag: Vec<(String, usize)>,
+    pub power: Answer,
+    /// every time a round showed more cubes of a colour than the bag holds
+    pub violations: Vec<Violation>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Violation {
+    /// numbered from 1, as in the puzzle text
+    pub round: usize,
+    pub colour: String,
+    pub shown: usize,
+    pub limit: usize,
+}
+
+impl Violation {
+    pub fn excess(&self) -> usize {
+        self.shown - self.limit
+    }
+}
+
+/// how the most cubes of one colour shown in a game is spread across all the games
+#[derive(Debug, PartialEq)]
+pub struct ColourMaxima {
+    pub colour: String,
+    /// for each maximum, how many games had it
+    pub games_by_maximum: BTreeMap<usize, usize>,
+}
+
+impl ColourMaxima {
+    /// counted in `f64`, so a large number of games cannot overflow it
+    pub fn mean(&self) -> f64 {
+        let (total, games) =
+            self.games_by_maximum
+                .iter()
+                .fold((0.0, 0.0), |(total, games), (maximum, count)| {
+                    (
+                        total + *maximum as f64 * *count as f64,
+                        games + *count as f64,
+                    )
+                });
+        match games == 0.0 {
+            true => 0.0,
+            false => total / games,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    pub games: Vec<GameReport>,
+    pub maxima: Vec<ColourMaxima>,
+    /// the games admitted by `tightest_bag`, which are the `top_k` games with the fewest cubes in their minimal bags
+    pub top_games: Vec<usize>,
+    pub tightest_bag: Vec<(String, usize)>,
+}
+
+/// helper function for the colours to report on: the bag's, then any others in the order they first appear
+fn report_colours(games: &[Game], rules: &BagRules) -> Vec<String> {
+    let mut colours: Vec<String> = rules
+        .limits
+        .iter()
+        .map(|(colour, _)| colour.clone())
+        .collect();
+    for colour in games.iter().flat_map(|game| game.colours()) {
+        if !colours.iter().any(|known| known == colour) {
+            colours.push(colour.to_string());
+        }
+    }
+    colours
+}
+
+/// fails if a game's minimal bag holds more cubes than a `usize` can count
+pub fn build_report(games: &[Game], rules: &BagRules, top_k: usize) -> Result<Report, NumberError> {
+    let colours = report_colours(games, rules);
+
+    let game_reports: Vec<GameReport> = games
+        .iter()
+        .map(|game| {
+            let mut violations = vec![];
+            for (index, round) in game.rounds.iter().enumerate() {
+                for colour in colours.iter() {
+                    let shown = round.get(colour).copied().unwrap_or(0);
+                    let limit = rules.limit(colour).unwrap_or(0);
+                    if shown > limit {
+                        violations.push(Violation {
+                            round: index + 1,
+                            colour: colour.clone(),
+                            shown,
+                            limit,
+                        });
+                    }
+                }
+            }
+            GameReport {
+                id: game.id,
+                minimal_bag: colours
+                    .iter()
+                    .map(|colour| (colour.clone(), game.max_count(colour)))
+                    .collect(),
+                power: rules.power(game),
+                violations,
+            }
+        })
+        .collect();
+
+    let maxima = colours
+        .iter()
+        .enumerate()
+        .map(|(index, colour)| {
+            let mut games_by_maximum = BTreeMap::new();
+            for game_report in game_reports.iter() {
+                *games_by_maximum
+                    .entry(game_report.minimal_bag[index].1)
+                    .or_insert(0) += 1;
+            }
+            ColourMaxima {
+                colour: colour.clone(),
+                games_by_maximum,
+            }
+        })
+        .collect();
+
+    let mut ranked: Vec<(usize, &GameReport)> = game_reports
+        .iter()
+        .map(|game_report| {
+            let location = Location::Named(format!("the minimal bag of game {}", game_report.id));
+            let cubes = checked_sum(
+                game_report
+                    .minimal_bag
+                    .iter()
+                    .map(|(_, count)| (*count, location.clone())),
+            )?;
+            Ok((cubes, game_report))
+        })
+        .collect::<Result<_, NumberError>>()?;
+    ranked.sort_by_key(|(cubes, game_report)| (*cubes, game_report.id));
+    ranked.truncate(top_k);
+    let tightest_bag = colours
+        .iter()
+        .enumerate()
+        .map(|(index, colour)| {
+            let count = ranked
+                .iter()
+                .map(|(_, game_report)| game_report.minimal_bag[index].1)
+                .max()
+                .unwrap_or(0);
+            (colour.clone(), count)
+        })
+        .collect();
+    let mut top_games: Vec<usize> = ranked
+        .iter()
+        .map(|(_, game_report)| game_report.id)
+        .collect();
+    top_games.sort();
+
+    Ok(Report {
+        games: game_reports,
+        maxima,
+        top_games,
+        tightest_bag,
+    })
+}
+
+/// helper function to write a bag like a round of the game
+fn format_bag(bag: &[(String, usize)]) -> String {
+    bag.iter()
+        .map(|(colour, count)| format!("{} {}", count, colour))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// helper function to write a string as a JSON string literal
+fn json_string(input: &str) -> String {
+    let mut output = String::from("\"");
+    for character in input.chars() {
+        match character {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            character if character.is_control() => {
+                output.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => output.push(character),
+        }
+    }
+    output.push('"');
+    output
+}
+
+/// helper function to write a bag as a JSON object from colour to count
+fn json_bag(bag: &[(String, usize)]) -> String {
+    let entries = bag
+        .iter()
+        .map(|(colour, count)| format!("{}: {}", json_string(colour), count))
+        .collect::<Vec<_>>();
+    format!("{{{}}}", entries.join(", "))
+}
+
+impl Report {
+    pub fn to_json(&self) -> String {
+        let games = self
+            .games
+            .iter()
+            .map(|game_report| {
+                let violations = game_report
+                    .violations
+                    .iter()
+                    .map(|violation| {
+                        format!(
+                            "{{\"round\": {}, \"colour\": {}, \"shown\": {}, \"limit\": {}, \"excess\": {}}}",
+                            violation.round,
+                            json_string(&violation.colour),
+                            violation.shown,
+                            violation.limit,
+                            violation.excess()
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!(
+                    "    {{\"id\": {}, \"minimal_bag\": {}, \"power\": {}, \"violations\": [{}]}}",
+                    game_report.id,
+                    json_bag(&game_report.minimal_bag),
+                    game_report.power,
+                    violations.join(", ")
+                )
+            })
+            .collect::<Vec<_>>();
+        let maxima = self
+            .maxima
+            .iter()
+            .map(|colour_maxima| {
+                let games_by_maximum = colour_maxima
+                    .games_by_maximum
+                    .iter()
+                    .map(|(maximum, count)| format!("\"{}\": {}", maximum, count))
+                    .collect::<Vec<_>>();
+                format!(
+                    "    {{\"colour\": {}, \"mean\": {:.3}, \"games_by_maximum\": {{{}}}}}",
+                    json_string(&colour_maxima.colour),
+                    colour_maxima.mean(),
+                    games_by_maximum.join(", ")
+                )
+            })
+            .collect::<Vec<_>>();
+        let top_games = self
+            .top_games
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>();
+        format!(
+            "{{\n  \"games\": [\n{}\n  ],\n  \"maxima\": [\n{}\n  ],\n  \"top_games\": [{}],\n  \"tightest_bag\": {}\n}}\n",
+            games.join(",\n"),
+            maxima.join(",\n"),
+            top_games.join(", "),
+            json_bag(&self.tightest_bag)
+        )
+    }
+}
+
+/// writes the report as a table of games followed by the aggregate statistics
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bags: Vec<String> = self
+            .games
+            .iter()
+            .map(|game_report| format_bag(&game_report.minimal_bag))
+            .collect();
+        let bag_width = bags.iter().map(|bag| bag.len()).max().unwrap_or(0).max(11);
+
+        writeln!(
+            f,
+            "{:>5}  {:<bag_width$}  {:>8}  violations",
+            "game", "minimal bag", "power"
+        )?;
+        for (game_report, bag) in self.games.iter().zip(bags.iter()) {
+            let violations = game_report
+                .violations
+                .iter()
+                .map(|violation| {
+                    format!(
+                        "round {}: {} {} (+{})",
+                        violation.round,
+                        violation.shown,
+                        violation.colour,
+                        violation.excess()
+                    )
+                })
+                .collect::<Vec<_>>();
+            writeln!(
+                f,
+                "{:>5}  {:<bag_width$}  {:>8}  {}",
+                game_report.id,
+                bag,
+                game_report.power,
+                match violations.is_empty() {
+                    true => "-".to_string(),
+                    false => violations.join("; "),
+                }
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "maxima per colour:")?;
+        for colour_maxima in self.maxima.iter() {
+            let games_by_maximum = colour_maxima
+                .games_by_maximum
+                .iter()
+                .map(|(maximum, count)| format!("{}:{}", maximum, count))
+                .collect::<Vec<_>>();
+            writeln!(
+                f,
+                "  {}: mean {:.2}; games per maximum {}",
+                colour_maxima.colour,
+                colour_maxima.mean(),
+                games_by_maximum.join(" ")
+            )?;
+        }
+        writeln!(
+            f,
+            "tightest bag for the {} game(s) with the smallest minimal bags: {}",
+            self.top_games.len(),
+            format_bag(&self.tightest_bag)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_report, Violation};
+    use crate::day_2::{load_games, BagRules};
+
+    fn example_report(top_k: usize) -> super::Report {
+        use std::fs;
+
+        let input =
+            fs::read_to_string("src/day_2/test_input_part_1.txt").expect("File read errored");
+        let rules = BagRules::default();
+        let (games, _) = load_games(&input, &rules).unwrap();
+        build_report(&games, &rules, top_k).unwrap()
+    }
+
+    #[test]
+    fn reports_minimal_bags_powers_and_violations() {
+        let report = example_report(2);
+
+        let game_3 = &report.games[2];
+        assert_eq!(
+            game_3.minimal_bag,
+            vec![
+                (String::from("red"), 20),
+                (String::from("green"), 13),
+                (String::from("blue"), 6),
+            ]
+        );
+        assert_eq!(game_3.power, 1560);
+        assert_eq!(
+            game_3.violations,
+            vec![Violation {
+                round: 1,
+                colour: String::from("red"),
+                shown: 20,
+                limit: 12
+            }]
+        );
+        assert_eq!(game_3.violations[0].excess(), 8);
+        assert!(report.games[0].violations.is_empty());
+    }
+
+    #[test]
+    fn aggregates_maxima_and_the_tightest_bag() {
+        let report = example_report(2);
+
+        let red = &report.maxima[0];
+        assert_eq!(red.games_by_maximum.values().sum::<usize>(), 5);
+        assert_eq!(red.games_by_maximum.get(&20), Some(&1));
+        assert_eq!(red.mean(), (4 + 1 + 20 + 14 + 6) as f64 / 5.0);
+        // games 2 (1 red, 3 green, 4 blue) and 5 (6 red, 3 green, 2 blue) need the fewest cubes
+        assert_eq!(report.top_games, vec![2, 5]);
+        assert_eq!(
+            report.tightest_bag,
+            vec![
+                (String::from("red"), 6),
+                (String::from("green"), 3),
+                (String::from("blue"), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_minimal_bags_too_large_to_rank() {
+        use crate::shared::{Location, NumberError};
+
+        let rules = BagRules::default();
+        let (games, _) = load_games(
+            &format!("Game 1: {} red, {} green", usize::MAX, usize::MAX),
+            &rules,
+        )
+        .unwrap();
+
+        let err = build_report(&games, &rules, 1).unwrap_err();
+        assert!(matches!(
+            err,
+            NumberError::Overflow {
+                location: Location::Named(_),
+                ..
+            }
+        ));
+        assert_eq!(
+            super::ColourMaxima {
+                colour: String::from("red"),
+                games_by_maximum: [(usize::MAX, 4)].into_iter().collect(),
+            }
+            .mean(),
+            usize::MAX as f64
+        );
+    }
+
+    #[test]
+    fn writes_json() {
+        let json = example_report(1).to_json();
+
+        assert!(json.contains("{\"id\": 3, \"minimal_bag\": {\"red\": 20, \"green\": 13, \"blue\": 6}, \"power\": 1560, \"violations\": [{\"round\": 1, \"colour\": \"red\", \"shown\": 20, \"limit\": 12, \"excess\": 8}]}"));
+        assert!(json.contains("\"top_games\": [2]"));
+        assert!(json.ends_with("\"tightest_bag\": {\"red\": 1, \"green\": 3, \"blue\": 4}\n}\n"));
+    }
+}
@@ -0,0 +1,133 @@
+use super::rules::{ByteKinds, CharacterKind, SchematicRules};
+use super::schematic::{cell_location, SchematicError};
+use crate::shared::{checked_add, push_digit, BitGrid, BitRow};
+
+/// the sum of the part numbers, found with packed masks rather than by visiting each number's neighbours:
+/// the symbol mask is dilated by one cell in every direction, and a number is a part if any of its digits
+/// lands under the dilated mask
+pub fn sum_part_numbers(input: &str, rules: &SchematicRules) -> Result<usize, SchematicError> {
+    let byte_kinds = ByteKinds::new(rules);
+    // blank lines are skipped, but each row remembers the line it came from for messages
+    let lines: Vec<(usize, &[u8])> = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_index, line)| (line_index, line.as_bytes()))
+        .collect();
+    let columns = lines.first().map_or(0, |(_, line)| line.len());
+
+    let mut symbols = BitGrid::new(lines.len(), columns);
+    let mut digits: Vec<BitRow> = Vec::with_capacity(lines.len());
+    for (row, (line_index, line)) in lines.iter().enumerate() {
+        // checked before the width, so a wide character is not mistaken for a ragged row
+        if let Some(column) = line.iter().position(|byte| !byte.is_ascii()) {
+            return Err(SchematicError::NonAscii {
+                row: *line_index,
+                column,
+            });
+        }
+        if line.len() != columns {
+            return Err(SchematicError::RaggedRow {
+                row: *line_index,
+                expected: columns,
+                found: line.len(),
+            });
+        }
+        let mut row_digits = BitRow::new(columns);
+        for (column, byte) in line.iter().enumerate() {
+            match byte_kinds.kind(*byte) {
+                CharacterKind::Digit(_) => row_digits.set(column),
+                CharacterKind::Symbol => symbols.set(row, column),
+                CharacterKind::Blank => {}
+                CharacterKind::Unknown => {
+                    return Err(SchematicError::UnknownCharacter {
+                        row: *line_index,
+                        column,
+                        character: *byte as char,
+                    })
+                }
+            }
+        }
+        digits.push(row_digits);
+    }
+
+    let near_symbols = symbols.dilated();
+    let mut total: usize = 0;
+    for (row, ((line_index, line), row_digits)) in lines.iter().zip(digits.iter()).enumerate() {
+        let touched = row_digits & near_symbols.row(row);
+        for run in row_digits.runs().filter(|run| touched.any_in(run.clone())) {
+            let location = cell_location(*line_index, run.start);
+            let value = line[run.clone()]
+                .iter()
+                .filter_map(|byte| byte_kinds.digit(*byte))
+                .try_fold(0usize, |value, digit| {
+                    push_digit(value, digit as u8, &location)
+                })?;
+            total = checked_add(total, value, &location)?;
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sum_part_numbers;
+    use crate::day_3::{rules::SchematicRules, schematic::Schematic};
+
+    #[test]
+    fn matches_the_schematic() {
+        use std::fs;
+
+        for path in ["src/day_3/test_input_part_1.txt", "src/day_3/input.txt"] {
+            let input = match fs::read_to_string(path) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in file reading: {err}"),
+            };
+            let schematic = match Schematic::parse(&input) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in Schematic::parse: {err}"),
+            };
+            let expected: usize = schematic.part_numbers().map(|number| number.value).sum();
+
+            assert_eq!(
+                sum_part_numbers(&input, &SchematicRules::default()),
+                Ok(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn handles_wide_rows() {
+        // a number straddling the first word boundary, touching a symbol diagonally below it
+        let top = format!("{}123{}", ".".repeat(62), ".".repeat(65));
+        let bottom = format!("{}#{}", ".".repeat(65), ".".repeat(64));
+
+        assert_eq!(
+            sum_part_numbers(&format!("{top}\n{bottom}\n"), &SchematicRules::default()),
+            Ok(123)
+        );
+    }
+
+    #[test]
+    fn locates_errors_by_line_past_blank_lines() {
+        use crate::day_3::schematic::SchematicError;
+
+        let rules = SchematicRules::default();
+        let overflowing = format!("{}\n\n*{}\n", ".".repeat(24), "9".repeat(23));
+
+        assert_eq!(
+            sum_part_numbers(&overflowing, &rules)
+                .unwrap_err()
+                .to_string(),
+            "Overflow multiplying at line 3, column 2"
+        );
+        assert_eq!(
+            sum_part_numbers("...\n\n..\n", &rules),
+            Err(SchematicError::RaggedRow {
+                row: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+}
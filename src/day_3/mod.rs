@@ -1,5 +1,20 @@
-use std::fs;
-use crate::shared::{get_chars, DayResult};
+use crate::shared::{checked_sum, DayResult, Location};
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    ops::Range,
+    time::Instant,
+};
+
+mod bitwise;
+mod render;
+mod rules;
+mod schematic;
+mod stream;
+
+pub use render::RenderOptions;
+pub use rules::{Aggregation, SchematicRules, SymbolSet};
+use schematic::Schematic;
 
 pub fn solve() -> Result<DayResult, String> {
     let input = match fs::read_to_string("src/day_3/input.txt") {
@@ -16,958 +31,154 @@ pub fn solve() -> Result<DayResult, String> {
         Err(err) => return Err(format!("Error in solve_part_2: {err}")),
     };
 
-    return Ok(DayResult { part_1: part_1, part_2: part_2});
+    Ok(DayResult {
+        part_1: part_1.into(),
+        part_2: part_2.into(),
+    })
 }
 
-fn solve_part_1(input: &str) -> Result<usize, String> {
-    let mut output: usize = 0;
-    let mut grid = ThreeRowGrid::new(None, None, None);
-
-    for line in input.lines() {
-        match grid.insert_next_row(line) {
-            Ok(_) => {}
-            Err(err) => return Err(format!("Error in ThreeGridRow.insert_new_row: {err}")),
-        }
-        output += match get_machine_part_numbers(&grid) {
-            Ok(result) => match result {
-                Some(vec) => vec.into_iter().sum(),
-                None => 0,
-            },
-            Err(err) => return Err(format!("Error in get_machine_part_numbers: {err}")),
-        }
-    }
-
-    match grid.handle_last_row() {
-        Ok(_) => {}
-        Err(err) => return Err(format!("Error in ThreeGridRow.handle_last_row: {err}")),
-    };
-
-    output += match get_machine_part_numbers(&grid) {
-        Ok(result) => match result {
-            Some(vec) => vec.into_iter().sum(),
-            None => 0,
-        },
-        Err(err) => return Err(format!("Error in get_machine_part_numbers: {err}")),
-    };
-
-    return Ok(output);
-}
-
-fn solve_part_2(input: &str) -> Result<usize, String> {
-    let mut output: usize = 0;
-    let mut grid = ThreeRowGrid::new(None, None, None);
-
-    for line in input.lines() {
-        match grid.insert_next_row(line) {
-            Ok(_) => {}
-            Err(err) => return Err(format!("Error in ThreeGridRow.insert_new_row: {err}")),
-        };
-        output += match get_gear_ratios(&grid) {
-            Ok(opt) => match opt {
-                Some(vec) => {
-                    let mut sum: usize = 0;
-                    for item in vec {
-                        println!("{item}");
-                        sum += item;
-                    }
-                    sum
-                },
-                None => 0,
-            },
-            Err(err) => return Err(format!("Error in get_gear_ratios: {err}")),
-        }
-    }
-
-    match grid.handle_last_row() {
-        Ok(_) => {}
-        Err(err) => return Err(format!("Error in ThreeGridRow.handle_last_row: {err}")),
-    };
-
-    output += match get_gear_ratios(&grid) {
-        Ok(result) => match result {
-            Some(vec) => vec.into_iter().sum(),
-            None => 0,
-        },
-        Err(err) => return Err(format!("Error in get_machine_part_numbers: {err}")),
-    };
-
-    return Ok(output);
-}
-
-fn get_gear_ratios(grid: &ThreeRowGrid) -> Result<Option<Vec<usize>>, String> {
-    match grid.get_case() {
-        ThreeRowGridCase::MiddleRowOnly | ThreeRowGridCase::Empty => return Ok(None),
-        ThreeRowGridCase::MiddleAndBottomRowOnly => {
-            if let (Some(middle_row), Some(bottom_row)) = (&grid.middle_row, &grid.bottom_row) {
-                get_gear_ratios_from_middle_and_bottom_row(middle_row.as_ref(), bottom_row.as_ref())
-            } else {
-                Err(String::from("Middle or Bottom row is missing"))
-            }
-        }
-        ThreeRowGridCase::TopAndMiddleRowOnly => {
-            if let (Some(top_row), Some(middle_row)) = (&grid.top_row, &grid.middle_row) {
-                get_gear_ratios_from_top_and_middle_row(top_row.as_ref(), middle_row.as_ref())
-            } else {
-                Err(String::from("Top or middle row is missing"))
-            }
-        }
-        ThreeRowGridCase::AllRows => {
-            if let (Some(top_row), Some(middle_row), Some(bottom_row)) =
-                (&grid.top_row, &grid.middle_row, &grid.bottom_row)
-            {
-                get_gear_ratios_from_all_rows(
-                    top_row.as_ref(),
-                    middle_row.as_ref(),
-                    bottom_row.as_ref(),
-                )
-            } else {
-                Err(String::from("Top, middle, or bottom row is missing"))
-            }
-        }
-        ThreeRowGridCase::Invalid => return Err(String::from("Cannot parse Invalid grid")),
-    }
-}
-
-fn get_gear_ratios_from_all_rows(
-    top_row: &Vec<char>,
-    middle_row: &Vec<char>,
-    bottom_row: &Vec<char>,
-) -> Result<Option<Vec<usize>>, String> {
-    let gears: Vec<bool> = middle_row
-        .iter()
-        .map(|ch| match ch {
-            '*' => true,
-            _ => false,
-        })
-        .collect();
-
-    let (_, top_number_map) = match get_bitmasks(top_row) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_bitmasks: {err}")),
-    };
-
-    let (_, middle_number_map) = match get_bitmasks(middle_row) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_bitmasks: {err}")),
-    };
-
-    let (_, bottom_number_map) = match get_bitmasks(bottom_row) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_bitmasks: {err}")),
-    };
-
-    let top_numbers = match get_all_numbers(top_row, top_number_map) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_all_numbers: {err}")),
-    };
-
-    let middle_numbers = match get_all_numbers(middle_row, middle_number_map) {
+/// solves both parts with different rules for symbols and gears
+pub fn solve_with(rules: &SchematicRules) -> Result<DayResult, String> {
+    let input = match fs::read_to_string("src/day_3/input.txt") {
         Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_all_numbers: {err}")),
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
     };
 
-    let bottom_numbers = match get_all_numbers(bottom_row, bottom_number_map) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_all_numbers: {err}")),
-    };
+    let part_1 = solve_part_1_with(&input, rules)?;
+    let part_2 = solve_part_2_with(&input, rules)?;
 
-    match merge_numbers([top_numbers, middle_numbers, bottom_numbers]) {
-        Some(result) => {
-            // if we have numbers
-            let output: Vec<usize> = gears
-                .into_iter()
-                .enumerate()
-                .filter_map(|(index, gear)| {
-                    if gear {
-                        match build_valid_gear(index, &result) {
-                            Some(fing) => {
-                                return Some(
-                                    fing.number_1.unwrap().value * fing.number_2.unwrap().value,
-                                )
-                            }
-                            None => return None,
-                        };
-                    } else {
-                        return None;
-                    }
-                })
-                .collect();
-            match output.len() {
-                0 => return Ok(None),
-                _ => return Ok(Some(output)),
-            };
-        }
-        None => return Ok(None), // if we don't have numbers, then we wont have gear ratios
-    };
+    Ok(DayResult {
+        part_1: part_1.into(),
+        part_2: part_2.into(),
+    })
 }
 
-fn get_gear_ratios_from_top_and_middle_row(
-    top_row: &Vec<char>,
-    middle_row: &Vec<char>,
-) -> Result<Option<Vec<usize>>, String> {
-    let gears: Vec<bool> = middle_row
-        .iter()
-        .map(|ch| match ch {
-            '*' => true,
-            _ => false,
-        })
-        .collect();
-
-    if !gears.iter().any(|b| *b) {
-        return Ok(None);
-    }
-
-    let (_, top_number_map) = match get_bitmasks(top_row) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_bitmasks: {err}")),
-    };
-
-    let (_, middle_number_map) = match get_bitmasks(middle_row) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_bitmasks: {err}")),
-    };
-
-    let top_numbers = match get_all_numbers(top_row, top_number_map) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_all_numbers: {err}")),
-    };
-
-    let middle_numbers = match get_all_numbers(middle_row, middle_number_map) {
+/// one line for every symbol in the schematic, listing the numbers attached to it and its value if it is a gear
+pub fn symbol_report(rules: &SchematicRules) -> Result<String, String> {
+    let input = match fs::read_to_string("src/day_3/input.txt") {
         Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_all_numbers: {err}")),
-    };
-
-    match merge_numbers([top_numbers, middle_numbers, None]) {
-        Some(result) => {
-            // if we have numbers
-            let output: Vec<usize> = gears
-                .into_iter()
-                .enumerate()
-                .filter_map(|(index, gear)| {
-                    if gear {
-                        match build_valid_gear(index, &result) {
-                            Some(fing) => {
-                                return Some(
-                                    fing.number_1.unwrap().value * fing.number_2.unwrap().value,
-                                )
-                            }
-                            None => return None,
-                        };
-                    } else {
-                        return None;
-                    }
-                })
-                .collect();
-            match output.len() {
-                0 => return Ok(None),
-                _ => return Ok(Some(output))
-            }
-        }
-        None => return Ok(None), // if we don't have numbers, then we wont have gear ratios
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
     };
-}
 
-fn get_gear_ratios_from_middle_and_bottom_row(
-    middle_row: &Vec<char>,
-    bottom_row: &Vec<char>,
-) -> Result<Option<Vec<usize>>, String> {
-    let gears: Vec<bool> = middle_row
+    let schematic = Schematic::parse_with(&input, rules).map_err(|err| err.to_string())?;
+    let reports = schematic
+        .symbol_reports(rules)
+        .map_err(|err| err.to_string())?;
+    Ok(reports
         .iter()
-        .map(|ch| match ch {
-            '*' => true,
-            _ => false,
-        })
-        .collect();
-
-    let (_, middle_number_map) = match get_bitmasks(middle_row) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_bitmasks: {err}")),
-    };
-
-    let (_, bottom_number_map) = match get_bitmasks(bottom_row) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_bitmasks: {err}")),
-    };
+        .map(|report| format!("{}\n", report))
+        .collect())
+}
 
-    let middle_numbers = match get_all_numbers(middle_row, middle_number_map) {
+/// solves both parts while reading the schematic at `path` a row at a time, see `stream::solve_streaming`
+pub fn solve_file_streaming(path: &str, rules: &SchematicRules) -> Result<DayResult, String> {
+    let file = match File::open(path) {
         Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_all_numbers: {err}")),
+        Err(err) => return Err(format!("Error in opening file: {}", err)),
     };
+    stream::solve_streaming(BufReader::new(file), rules).map_err(|err| err.to_string())
+}
 
-    let bottom_numbers = match get_all_numbers(bottom_row, bottom_number_map) {
+/// draws the schematic in colour, see `render::render`
+pub fn render_schematic(rules: &SchematicRules, options: &RenderOptions) -> Result<String, String> {
+    let input = match fs::read_to_string("src/day_3/input.txt") {
         Ok(result) => result,
-        Err(err) => return Err(format!("Error in get_all_numbers: {err}")),
-    };
-
-    match merge_numbers([None, middle_numbers, bottom_numbers]) {
-        Some(result) => {
-            // if we have numbers
-            let output: Vec<usize> = gears
-                .into_iter()
-                .enumerate()
-                .filter_map(|(index, gear)| {
-                    if gear {
-                        match build_valid_gear(index, &result) {
-                            Some(fing) => {
-                                return Some(
-                                    fing.number_1.unwrap().value * fing.number_2.unwrap().value,
-                                )
-                            }
-                            None => return None,
-                        };
-                    } else {
-                        return None;
-                    }
-                })
-                .collect();
-            match output.len() {
-                0 => return Ok(None),
-                _ => return Ok(Some(output))
-            }
-        }
-        None => return Ok(None), // if we don't have numbers, then we wont have gear ratios
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
     };
-}
 
-struct Gear<'a> {
-    index: usize,
-    number_1: Option<&'a Number>,
-    number_2: Option<&'a Number>,
+    let schematic = Schematic::parse_with(&input, rules).map_err(|err| err.to_string())?;
+    render::render(&schematic, rules, options).map_err(|err| err.to_string())
 }
 
-fn build_valid_gear(index: usize, numbers: &Vec<Number>) -> Option<Gear> {
-    let mut gear = Gear {
-        index: index,
-        number_1: None,
-        number_2: None,
+/// reads a window of rows or columns written `first..last`, counting from 1 and including both ends,
+/// or a single row or column
+pub fn parse_window(input: &str) -> Result<Range<usize>, String> {
+    let parse = |value: &str| match value.trim().parse::<usize>() {
+        Ok(0) => Err(String::from("Rows and columns are counted from 1")),
+        Ok(value) => Ok(value),
+        Err(err) => Err(format!("Invalid value `{}`: {}", value, err)),
     };
-    for number in numbers {
-        // check if nearby (we don't care about which row it comes from)
-        if number.start <= gear.index + 1 && gear.index <= number.stop {
-            match (gear.number_1.is_some(), gear.number_2.is_some()) {
-                (false, false) => gear.number_1 = Some(number),
-                (true, false) => {
-                    gear.number_2 = Some(number);
-                    return Some(gear);
-                }
-                _ => {}
-            }
-        }
-    }
-    return None;
-}
-
-fn merge_numbers(rows: [Option<Vec<Number>>; 3]) -> Option<Vec<Number>> {
-    let mut merged: Vec<Number> = Vec::new();
-
-    for row in rows.into_iter() {
-        match row {
-            Some(result) => merged.extend(result.into_iter()),
-            None => {}
-        }
-    }
-
-    match merged.len() {
-        0 => return None,
-        _ => return Some(merged),
-    }
-}
-
-struct ThreeRowGrid {
-    top_row: Option<Vec<char>>,
-    middle_row: Option<Vec<char>>,
-    bottom_row: Option<Vec<char>>,
-}
-
-#[derive(Debug, PartialEq)]
-enum ThreeRowGridCase {
-    MiddleRowOnly,
-    TopAndMiddleRowOnly,
-    MiddleAndBottomRowOnly,
-    AllRows,
-    Empty,
-    Invalid,
-}
-
-impl ThreeRowGrid {
-    fn new(
-        top_row: Option<&str>,
-        middle_row: Option<&str>,
-        bottom_row: Option<&str>,
-    ) -> ThreeRowGrid {
-        let mut collector: Vec<&str> = Vec::new();
-        match top_row {
-            Some(row) => collector.push(row),
-            _ => {}
-        }
-        match middle_row {
-            Some(row) => collector.push(row),
-            _ => {}
-        }
-        match bottom_row {
-            Some(row) => collector.push(row),
-            _ => {}
-        }
-        match collector.len() {
-            1 => {
-                return ThreeRowGrid {
-                    top_row: None,
-                    middle_row: Some(get_chars(collector[0])),
-                    bottom_row: None,
-                }
-            }
-            2 => {
-                return ThreeRowGrid {
-                    top_row: None,
-                    middle_row: Some(get_chars(collector[0])),
-                    bottom_row: Some(get_chars(collector[1])),
-                }
-            }
-            3 => {
-                return ThreeRowGrid {
-                    top_row: Some(get_chars(collector[0])),
-                    middle_row: Some(get_chars(collector[1])),
-                    bottom_row: Some(get_chars(collector[2])),
-                }
-            }
-            _ => {
-                // return empty
-                return ThreeRowGrid {
-                    top_row: None,
-                    middle_row: None,
-                    bottom_row: None,
-                };
+    match input.split_once("..") {
+        Some((first, last)) => {
+            let (first, last) = (parse(first)?, parse(last)?);
+            match last < first {
+                true => Err(format!("Window `{}` ends before it starts", input)),
+                false => Ok(first - 1..last),
             }
         }
-    }
-
-    fn get_case(&self) -> ThreeRowGridCase {
-        let top_row: bool = match &self.top_row {
-            Some(_row) => true,
-            None => false,
-        };
-        let middle_row: bool = match &self.middle_row {
-            Some(_row) => true,
-            None => false,
-        };
-        let bottom_row: bool = match &self.bottom_row {
-            Some(_row) => true,
-            None => false,
-        };
-
-        match (top_row, middle_row, bottom_row) {
-            (true, true, true) => return ThreeRowGridCase::AllRows,
-            (true, true, false) => return ThreeRowGridCase::TopAndMiddleRowOnly,
-            (false, true, true) => return ThreeRowGridCase::MiddleAndBottomRowOnly,
-            (false, true, false) => return ThreeRowGridCase::MiddleRowOnly,
-            (false, false, false) => return ThreeRowGridCase::Empty,
-            _ => return ThreeRowGridCase::Invalid,
+        None => {
+            let value = parse(input)?;
+            Ok(value - 1..value)
         }
     }
-
-    fn insert_next_row(&mut self, row: &str) -> Result<(), String> {
-        match self.get_case() {
-            ThreeRowGridCase::Empty => {
-                self.middle_row = Some(get_chars(row));
-                return Ok(());
-            }
-            ThreeRowGridCase::MiddleRowOnly => {
-                self.bottom_row = Some(get_chars(row));
-                return Ok(());
-            }
-            ThreeRowGridCase::AllRows | ThreeRowGridCase::MiddleAndBottomRowOnly => {
-                self.top_row = self.middle_row.take(); // yoink
-                self.middle_row = self.bottom_row.take();
-                self.bottom_row = Some(get_chars(row));
-                return Ok(());
-            }
-            ThreeRowGridCase::TopAndMiddleRowOnly => {
-                return Err(String::from("Row inserted into end case"))
-            }
-            ThreeRowGridCase::Invalid => {
-                return Err(String::from("Unable to add row to an invalid ThreeRowGrid"))
-            }
-        }
-    }
-
-    fn handle_last_row(&mut self) -> Result<(), String> {
-        match self.get_case() {
-            ThreeRowGridCase::AllRows | ThreeRowGridCase::MiddleAndBottomRowOnly => {
-                self.top_row = self.middle_row.take(); // yoink
-                self.middle_row = self.bottom_row.take();
-                self.bottom_row = None;
-                return Ok(());
-            }
-            _ => return Err(String::from("Invalid end case")),
-        }
-    }
-}
-
-fn get_machine_part_numbers(grid: &ThreeRowGrid) -> Result<Option<Vec<usize>>, String> {
-    match grid.get_case() {
-        ThreeRowGridCase::Empty => Ok(None),
-        ThreeRowGridCase::Invalid => Err(String::from("Unable to parse Invalid ThreeRowGrid")),
-        ThreeRowGridCase::MiddleRowOnly => Ok(None),
-        ThreeRowGridCase::MiddleAndBottomRowOnly => {
-            if let (Some(middle_row), Some(bottom_row)) = (&grid.middle_row, &grid.bottom_row) {
-                get_machine_part_numbers_from_middle_and_bottom_row(
-                    middle_row.as_ref(),
-                    bottom_row.as_ref(),
-                )
-            } else {
-                Err(String::from("Middle or Bottom row is missing"))
-            }
-        }
-        ThreeRowGridCase::TopAndMiddleRowOnly => {
-            if let (Some(top_row), Some(middle_row)) = (&grid.top_row, &grid.middle_row) {
-                get_machine_part_numbers_from_top_and_middle_row(
-                    top_row.as_ref(),
-                    middle_row.as_ref(),
-                )
-            } else {
-                Err(String::from("Top or middle row is missing"))
-            }
-        }
-        ThreeRowGridCase::AllRows => {
-            if let (Some(top_row), Some(middle_row), Some(bottom_row)) =
-                (&grid.top_row, &grid.middle_row, &grid.bottom_row)
-            {
-                get_machine_part_numbers_from_all_rows(
-                    top_row.as_ref(),
-                    middle_row.as_ref(),
-                    bottom_row.as_ref(),
-                )
-            } else {
-                Err(String::from("Top, middle, or bottom row is missing"))
-            }
-        }
-    }
-}
-
-fn get_machine_part_numbers_from_top_and_middle_row(
-    top_row: &Vec<char>,
-    middle_row: &Vec<char>,
-) -> Result<Option<Vec<usize>>, String> {
-    let t_symbol_bitmask = match get_bitmasks(top_row) {
-        Ok(result) => result.0,
-        Err(err) => return Err(format!("Error from bitmasks: {err}")),
-    };
-
-    let (m_symbol_bitmask, m_num_bitmask) = match get_bitmasks(middle_row) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error from bitmasks: {err}")),
-    };
-
-    let numbers = match get_all_numbers(middle_row, m_num_bitmask) {
-        Ok(result) => match result {
-            Some(nums) => nums,
-            None => return Ok(None),
-        },
-        Err(err) => return Err(format!("Error in get_all_numbers: {err}")),
-    };
-
-    let output: Vec<usize> = numbers
-        .into_iter()
-        .filter(|num| match num.start {
-            0 => {
-                let post = match m_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let above = t_symbol_bitmask[num.start..num.stop]
-                    .iter()
-                    .any(|val| val == &true);
-                let upper_right = match t_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                return post || above || upper_right;
-            }
-            _ => {
-                let pre = match m_symbol_bitmask.get(num.start - 1) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let post = match m_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let above = t_symbol_bitmask[num.start..num.stop]
-                    .iter()
-                    .any(|val| val == &true);
-                let upper_left = match t_symbol_bitmask.get(num.start - 1) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let upper_right = match t_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                return pre || post || upper_left || above || upper_right;
-            }
-        })
-        .map(|num| num.value)
-        .collect::<Vec<_>>();
-
-    match output.len() {
-        0 => return Ok(None),
-        _ => return Ok(Some(output)),
-    };
-}
-
-fn get_machine_part_numbers_from_middle_and_bottom_row(
-    middle_row: &Vec<char>,
-    bottom_row: &Vec<char>,
-) -> Result<Option<Vec<usize>>, String> {
-    let (m_symbol_bitmask, m_num_bitmask) = match get_bitmasks(middle_row) {
-        Ok(result) => result,
-        Err(err) => return Err(format!("Error from bitmasks: {err}")),
-    };
-
-    let b_symbol_bitmask = match get_bitmasks(bottom_row) {
-        Ok(result) => result.0,
-        Err(err) => return Err(format!("Error from bitmasks: {err}")),
-    };
-
-    let numbers = match get_all_numbers(middle_row, m_num_bitmask) {
-        Ok(result) => match result {
-            Some(nums) => nums,
-            None => return Ok(None),
-        },
-        Err(err) => return Err(format!("Error in get_all_numbers: {err}")),
-    };
-
-    let output: Vec<usize> = numbers
-        .into_iter()
-        .filter(|num| match num.start {
-            0 => {
-                let post = match m_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let below = b_symbol_bitmask[num.start..num.stop]
-                    .iter()
-                    .any(|val| val == &true);
-                let lower_right = match b_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                return post || below || lower_right;
-            }
-            _ => {
-                let pre = match m_symbol_bitmask.get(num.start - 1) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let post = match m_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let below = b_symbol_bitmask[num.start..num.stop]
-                    .iter()
-                    .any(|val| val == &true);
-                let lower_left = match b_symbol_bitmask.get(num.start - 1) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let lower_right = match b_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                return pre || post || lower_left || below || lower_right;
-            }
-        })
-        .map(|num| num.value)
-        .collect::<Vec<_>>();
-
-    match output.len() {
-        0 => return Ok(None),
-        _ => return Ok(Some(output)),
-    };
 }
 
-fn get_machine_part_numbers_from_all_rows(
-    top_row: &Vec<char>,
-    middle_row: &Vec<char>,
-    bottom_row: &Vec<char>,
-) -> Result<Option<Vec<usize>>, String> {
-    let t_symbol_bitmask = match get_bitmasks(top_row) {
-        Ok(result) => result.0,
-        Err(err) => return Err(format!("Error from bitmasks: {err}")),
-    };
-
-    let (m_symbol_bitmask, m_num_bitmask) = match get_bitmasks(middle_row) {
+/// times part 1 on the input stacked `copies` times, with the indexed schematic, the packed masks and the
+/// streaming reader
+pub fn benchmark_part_1(copies: usize) -> Result<String, String> {
+    let input = match fs::read_to_string("src/day_3/input.txt") {
         Ok(result) => result,
-        Err(err) => return Err(format!("Error from bitmasks: {err}")),
-    };
-
-    let b_symbol_bitmask = match get_bitmasks(bottom_row) {
-        Ok(result) => result.0,
-        Err(err) => return Err(format!("Error from bitmasks: {err}")),
-    };
-
-    let numbers = match get_all_numbers(middle_row, m_num_bitmask) {
-        Ok(result) => match result {
-            Some(nums) => nums,
-            None => return Ok(None),
-        },
-        Err(err) => return Err(format!("Error in get_all_numbers: {err}")),
-    };
-
-    let output: Vec<usize> = numbers
-        .into_iter()
-        .filter(|num| match num.start {
-            0 => {
-                let post = match m_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let above = t_symbol_bitmask[num.start..num.stop]
-                    .iter()
-                    .any(|val| val == &true);
-                let upper_right = match t_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let below = b_symbol_bitmask[num.start..num.stop]
-                    .iter()
-                    .any(|val| val == &true);
-                let lower_right = match b_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                return post || above || upper_right || below || lower_right;
-            }
-            _ => {
-                let pre = match m_symbol_bitmask.get(num.start - 1) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let post = match m_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let above = t_symbol_bitmask[num.start..num.stop]
-                    .iter()
-                    .any(|val| val == &true);
-                let upper_left = match t_symbol_bitmask.get(num.start - 1) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let upper_right = match t_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let below = b_symbol_bitmask[num.start..num.stop]
-                    .iter()
-                    .any(|val| val == &true);
-                let lower_left = match b_symbol_bitmask.get(num.start - 1) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                let lower_right = match b_symbol_bitmask.get(num.stop) {
-                    Some(val) => match val {
-                        true => true,
-                        false => false,
-                    },
-                    None => false,
-                };
-                return pre
-                    || post
-                    || upper_left
-                    || above
-                    || upper_right
-                    || lower_left
-                    || below
-                    || lower_right;
-            }
-        })
-        .map(|num| num.value)
-        .collect::<Vec<_>>();
-
-    match output.len() {
-        0 => return Ok(None),
-        _ => return Ok(Some(output)),
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
     };
+    let input = format!("{}\n", input.trim_end()).repeat(copies);
+    let rules = SchematicRules::default();
+
+    let start = Instant::now();
+    let schematic = Schematic::parse_with(&input, &rules).map_err(|err| err.to_string())?;
+    let schematic_total: usize = schematic.part_numbers().map(|number| number.value).sum();
+    let schematic_time = start.elapsed();
+
+    let start = Instant::now();
+    let bitwise_total = bitwise::sum_part_numbers(&input, &rules).map_err(|err| err.to_string())?;
+    let bitwise_time = start.elapsed();
+
+    let start = Instant::now();
+    let streamed_total = stream::solve_streaming(input.as_bytes(), &rules)
+        .map_err(|err| err.to_string())?
+        .part_1;
+    let streamed_time = start.elapsed();
+
+    if schematic_total != bitwise_total || streamed_total != schematic_total {
+        return Err(format!(
+            "Part 1 paths disagree: schematic gave {}, masks gave {}, streaming gave {}",
+            schematic_total, bitwise_total, streamed_total
+        ));
+    }
+    Ok(format!(
+        "{} copies of the input ({} bytes), part 1 is {}\n  schematic: {:?}\n  packed masks: {:?}\n  streaming (both parts): {:?}",
+        copies,
+        input.len(),
+        schematic_total,
+        schematic_time,
+        bitwise_time,
+        streamed_time
+    ))
 }
 
-fn get_bitmasks(row: &Vec<char>) -> Result<(Vec<bool>, Vec<bool>), String> {
-    let mut symbols: Vec<bool> = vec![false; row.len()];
-    let mut numbers: Vec<bool> = vec![false; row.len()];
-
-    enum CharType {
-        FullStop,
-        Numeric,
-        Symbol,
-    }
-
-    fn get_char_type(input: &char) -> CharType {
-        match input {
-            '.' => return CharType::FullStop,
-            '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => return CharType::Numeric,
-            _ => return CharType::Symbol,
-        }
-    }
-
-    for (index, ch) in row.iter().enumerate() {
-        match get_char_type(ch) {
-            CharType::FullStop => {}
-            CharType::Numeric => numbers[index] = true,
-            CharType::Symbol => symbols[index] = true,
-        }
-    }
-
-    return Ok((symbols, numbers));
-}
-
-fn get_int_part_1(input: &char) -> Option<usize> {
-    match input {
-        '0' => return Some(0),
-        '1' => return Some(1),
-        '2' => return Some(2),
-        '3' => return Some(3),
-        '4' => return Some(4),
-        '5' => return Some(5),
-        '6' => return Some(6),
-        '7' => return Some(7),
-        '8' => return Some(8),
-        '9' => return Some(9),
-        _ => return None,
-    }
+fn solve_part_1(input: &str) -> Result<usize, String> {
+    solve_part_1_with(input, &SchematicRules::default())
 }
 
-struct Number {
-    start: usize,
-    stop: usize,
-    value: usize,
+fn solve_part_1_with(input: &str, rules: &SchematicRules) -> Result<usize, String> {
+    bitwise::sum_part_numbers(input, rules).map_err(|err| err.to_string())
 }
 
-impl PartialEq for Number {
-    fn eq(&self, other: &Self) -> bool {
-        if self.start != other.start {
-            return false;
-        } else if self.stop != other.stop {
-            return false;
-        } else if self.value != other.value {
-            return false;
-        } else {
-            return true;
-        }
-    }
+fn solve_part_2(input: &str) -> Result<usize, String> {
+    solve_part_2_with(input, &SchematicRules::default())
 }
 
-fn get_all_numbers(row: &Vec<char>, bitmask: Vec<bool>) -> Result<Option<Vec<Number>>, String> {
-    let mut output: Vec<Number> = Vec::new();
-
-    let mut i: usize = 0;
-
-    while i < row.len() {
-        match bitmask.get(i) {
-            Some(val) => match val {
-                true => {
-                    let mut j = 1;
-                    while match bitmask.get(i + j) {
-                        Some(result) => match result {
-                            true => true,
-                            false => false,
-                        },
-                        None => {
-                            if i + j == row.len() {
-                                false // end of row
-                            } else {
-                                return Err(String::from("Accessed a bit value out of scope"));
-                            }
-                        }
-                    } {
-                        j += 1;
-                    }
-                    output.push(Number {
-                        start: i,
-                        stop: i + j,
-                        value: row[i..i + j]
-                            .iter()
-                            .rev()
-                            .enumerate()
-                            .map(|(index, value)| {
-                                return match get_int_part_1(value) {
-                                    Some(result) => result * 10u32.pow(index as u32) as usize,
-                                    None => 0,
-                                };
-                            })
-                            .sum(),
-                    });
-                    i += j;
-                }
-                false => i += 1,
-            },
-            None => return Err(String::from("Accessed a bit value out of scope")),
-        }
-    }
-
-    match output.len() {
-        0 => return Ok(None),
-        _ => return Ok(Some(output)),
-    }
+fn solve_part_2_with(input: &str, rules: &SchematicRules) -> Result<usize, String> {
+    let schematic = Schematic::parse_with(input, rules).map_err(|err| err.to_string())?;
+    let gear_values = schematic
+        .gear_values(rules)
+        .map_err(|err| err.to_string())?;
+    checked_sum(
+        gear_values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| (value, Location::Named(format!("gear {}", index + 1)))),
+    )
+    .map_err(|err| err.to_string())
 }
 
 #[cfg(test)]
@@ -1012,465 +223,29 @@ mod tests {
         }
     }
 
-    mod test_three_row_grid {
-
-        #[test]
-        fn initialises_empty() {
-            use super::{ThreeRowGrid, ThreeRowGridCase};
-
-            let result = ThreeRowGrid::new(None, None, None);
-
-            assert_eq!(result.get_case(), ThreeRowGridCase::Empty)
-        }
-
-        #[test]
-        fn initialises_middle_only() {
-            use super::{ThreeRowGrid, ThreeRowGridCase};
-
-            let result = ThreeRowGrid::new(Some("467..114.."), None, None);
-            assert_eq!(result.get_case(), ThreeRowGridCase::MiddleRowOnly);
-
-            let result = ThreeRowGrid::new(None, Some("467..114.."), None);
-            assert_eq!(result.get_case(), ThreeRowGridCase::MiddleRowOnly);
-
-            let result = ThreeRowGrid::new(None, None, Some("467..114.."));
-            assert_eq!(result.get_case(), ThreeRowGridCase::MiddleRowOnly);
-        }
-
-        #[test]
-        fn initialises_top_and_middle_only() {
-            use super::{ThreeRowGrid, ThreeRowGridCase};
-
-            let result = ThreeRowGrid::new(Some("467..114.."), Some("...*......"), None);
-            assert_eq!(result.get_case(), ThreeRowGridCase::MiddleAndBottomRowOnly);
-
-            let result = ThreeRowGrid::new(Some("467..114.."), None, Some("...*......"));
-            assert_eq!(result.get_case(), ThreeRowGridCase::MiddleAndBottomRowOnly);
-
-            let result = ThreeRowGrid::new(None, Some("467..114.."), Some("...*......"));
-            assert_eq!(result.get_case(), ThreeRowGridCase::MiddleAndBottomRowOnly);
-        }
-
-        #[test]
-        fn initialises_all_rows() {
-            use super::{ThreeRowGrid, ThreeRowGridCase};
-
-            let result =
-                ThreeRowGrid::new(Some("467..114.."), Some("...*......"), Some("..35..633."));
-
-            assert_eq!(result.get_case(), ThreeRowGridCase::AllRows)
-        }
-
-        #[test]
-        fn insert_next_row_when_empty() {
-            use super::{get_chars, ThreeRowGrid, ThreeRowGridCase};
-
-            let row_1 = "467..114..";
-
-            let row_1_chars = get_chars(&row_1);
-
-            let mut grid = ThreeRowGrid {
-                top_row: None,
-                middle_row: None,
-                bottom_row: None,
-            };
-
-            match grid.insert_next_row(row_1) {
-                Ok(_) => {}
-                Err(err) => panic!("Error in insert_next_row: {err}"),
-            }
-
-            assert!(&grid.top_row.is_none());
-            assert!(&grid.middle_row.is_some());
-            assert!(&grid.bottom_row.is_none());
-            assert_eq!(grid.middle_row.clone().unwrap(), row_1_chars);
-            assert_eq!(grid.get_case(), ThreeRowGridCase::MiddleRowOnly)
-        }
-
-        #[test]
-        fn insert_bottom_row_when_middle_only() {
-            use super::{get_chars, ThreeRowGrid, ThreeRowGridCase};
-
-            let row_1 = "467..114..";
-            let row_1_chars = get_chars(&row_1);
-
-            let row_2 = "...*......";
-            let row_2_chars = get_chars(&row_2);
-
-            let mut grid = ThreeRowGrid {
-                top_row: None,
-                middle_row: Some(row_1_chars.clone()),
-                bottom_row: None,
-            };
-
-            match grid.insert_next_row(row_2) {
-                Ok(_) => {}
-                Err(err) => panic!("Error in insert_next_row: {err}"),
-            }
-
-            assert!(&grid.top_row.is_none());
-            assert!(&grid.middle_row.is_some());
-            assert!(&grid.bottom_row.is_some());
-            assert_eq!(grid.get_case(), ThreeRowGridCase::MiddleAndBottomRowOnly);
-            assert_eq!(grid.middle_row.clone().unwrap(), row_1_chars);
-            assert_eq!(grid.bottom_row.clone().unwrap(), row_2_chars);
-        }
-
-        #[test]
-        fn insert_bottom_row_when_top_and_middle_only() {
-            use super::{get_chars, ThreeRowGrid, ThreeRowGridCase};
-
-            let row_1 = "467..114..";
-            let row_1_chars = get_chars(&row_1);
-
-            let row_2 = "...*......";
-            let row_2_chars = get_chars(&row_2);
-
-            let row_3 = "..35..633.";
-            let row_3_chars = get_chars(&row_3);
-
-            let mut grid = ThreeRowGrid {
-                top_row: None,
-                middle_row: Some(row_1_chars.clone()),
-                bottom_row: Some(row_2_chars.clone()),
-            };
-
-            match grid.insert_next_row(row_3) {
-                Ok(_) => {}
-                Err(err) => panic!("Error in insert_next_row: {err}"),
-            }
-
-            assert!(&grid.top_row.is_some());
-            assert!(&grid.middle_row.is_some());
-            assert!(&grid.bottom_row.is_some());
-            assert_eq!(grid.get_case(), ThreeRowGridCase::AllRows);
-            assert_eq!(grid.top_row.clone().unwrap(), row_1_chars);
-            assert_eq!(grid.middle_row.clone().unwrap(), row_2_chars);
-            assert_eq!(grid.bottom_row.clone().unwrap(), row_3_chars);
-        }
-
-        #[test]
-        fn insert_bottom_row_when_all_rows_filled() {
-            use super::{get_chars, ThreeRowGrid, ThreeRowGridCase};
-
-            let row_1 = "467..114..";
-            let row_1_chars = get_chars(&row_1);
-
-            let row_2 = "...*......";
-            let row_2_chars = get_chars(&row_2);
-
-            let row_3 = "..35..633.";
-            let row_3_chars = get_chars(&row_3);
-
-            let row_4 = "......#...";
-            let row_4_chars = get_chars(&row_4);
-
-            let mut grid = ThreeRowGrid {
-                top_row: Some(row_1_chars.clone()),
-                middle_row: Some(row_2_chars.clone()),
-                bottom_row: Some(row_3_chars.clone()),
-            };
-
-            match grid.insert_next_row(row_4) {
-                Ok(_) => {}
-                Err(err) => panic!("Error in insert_next_row: {err}"),
-            }
-
-            assert!(&grid.top_row.is_some());
-            assert!(&grid.middle_row.is_some());
-            assert!(&grid.bottom_row.is_some());
-            assert_eq!(grid.get_case(), ThreeRowGridCase::AllRows);
-            assert_eq!(grid.top_row.clone().unwrap(), row_2_chars);
-            assert_eq!(grid.middle_row.clone().unwrap(), row_3_chars);
-            assert_eq!(grid.bottom_row.clone().unwrap(), row_4_chars);
-        }
-    }
-
-    mod test_get_machine_numbers {
-
-        #[test]
-        fn test_top_and_middle_row() {
-            use super::{get_chars, get_machine_part_numbers_from_top_and_middle_row};
-
-            let result = match get_machine_part_numbers_from_top_and_middle_row(
-                &get_chars("*....^.71."),
-                &get_chars("76.1...&7."),
-            ) {
-                Ok(result) => result,
-                Err(err) => {
-                    panic!("Error in get_machine_part_numbers_from_top_and_middle_row: {err}")
-                }
-            };
-
-            assert!(result.is_some());
-            assert_eq!(result.unwrap(), vec![76 as usize, 7 as usize])
-        }
-
-        #[test]
-        fn test_all_rows() {
-            use super::{get_chars, get_machine_part_numbers_from_all_rows};
-
-            let result = match get_machine_part_numbers_from_all_rows(&get_chars(".679.....662....71............................805..........862.680...................................................................687...."),
-             &get_chars("............*....-..811..........846..855......*.............*..$........230.92@............................=.....................92........"),
-              &get_chars("..........360..........#....664.....=.*...881...677...934.780.......426.*..........8......654.....*959.....539..........21.........*........")){
-                Ok(result) => result,
-                Err(err) => panic!("Error in get_machine_part_numbers_from_all_rows: {err}"),
-            };
-
-            assert!(result.is_some());
-            assert_eq!(result.unwrap(), Vec::from([811, 846, 855, 230, 92, 92]));
-
-            let result = match get_machine_part_numbers_from_all_rows(&get_chars("............*....-..811..........846..855......*.............*..$........230.92@............................=.....................92........"),
-              &get_chars("..........360..........#....664.....=.*...881...677...934.780.......426.*..........8......654.....*959.....539..........21.........*........"),
-              &get_chars(".....................+.........*......379..*.........*.........=.........969........*........*.976..............872....*....../....579......")){
-                Ok(result) => result,
-                Err(err) => panic!("Error in get_machine_part_numbers_from_all_rows: {err}"),
-            };
-
-            assert!(result.is_some());
-            assert_eq!(
-                result.unwrap(),
-                Vec::from([360, 664, 881, 677, 934, 780, 8, 654, 959, 539, 21])
-            );
-        }
+    #[test]
+    fn parses_windows() {
+        assert_eq!(parse_window("3..9"), Ok(2..9));
+        assert_eq!(parse_window("4..4"), Ok(3..4));
+        assert_eq!(parse_window("7"), Ok(6..7));
+        assert_eq!(
+            parse_window("9..3"),
+            Err(String::from("Window `9..3` ends before it starts"))
+        );
+        assert!(parse_window("0..3").is_err());
+        assert!(parse_window("a..3").is_err());
     }
 
-    mod test_get_bitmasks {
-
-        #[test]
-        fn returns_all_false_with_full_stops() {
-            use super::get_bitmasks;
-            use super::get_chars;
+    #[test]
+    fn both_parts_reject_non_ascii() {
+        // as many characters as the row above, so only the byte count gives it away
+        let expected = Err(String::from("Non-ASCII byte at row 2, column 3"));
+        assert_eq!(solve_part_1("...\n..é\n"), expected);
+        assert_eq!(solve_part_2("...\n..é\n"), expected);
 
-            let row = get_chars(".......");
-
-            let result = match get_bitmasks(&row) {
-                Ok(result) => result,
-                Err(_err) => panic!(),
-            };
-
-            assert!(result.0.iter().all(|val| val == &false));
-            assert!(result.1.iter().all(|val| val == &false));
-            assert_eq!(row.len(), result.0.len());
-            assert_eq!(row.len(), result.1.len());
-        }
-
-        #[test]
-        fn finds_symbols() {
-            use super::get_bitmasks;
-            use super::get_chars;
-
-            let row = get_chars(".*.%.$.");
-
-            let result = match get_bitmasks(&row) {
-                Ok(result) => result,
-                Err(_err) => panic!(),
-            };
-
-            assert_eq!(result.0, vec![false, true, false, true, false, true, false]);
-            assert!(result.1.iter().all(|val| val == &false));
-            assert_eq!(row.len(), result.0.len());
-            assert_eq!(row.len(), result.1.len());
-        }
-
-        #[test]
-        fn finds_numbers() {
-            use super::get_bitmasks;
-            use super::get_chars;
-
-            let row = get_chars(".12.");
-
-            let result = match get_bitmasks(&row) {
-                Ok(result) => result,
-                Err(_err) => panic!(),
-            };
-
-            assert!(result.0.iter().all(|val| val == &false));
-            assert_eq!(result.1, vec![false, true, true, false]);
-            assert_eq!(row.len(), result.0.len());
-            assert_eq!(row.len(), result.1.len());
-        }
-
-        #[test]
-        fn finds_both() {
-            use super::get_bitmasks;
-            use super::get_chars;
-
-            let row = get_chars(".1*.");
-
-            let result = match get_bitmasks(&row) {
-                Ok(result) => result,
-                Err(_err) => panic!(),
-            };
-
-            assert_eq!(result.0, vec![false, false, true, false]);
-            assert_eq!(result.1, vec![false, true, false, false]);
-            assert_eq!(row.len(), result.0.len());
-            assert_eq!(row.len(), result.1.len());
-        }
-    }
-    mod test_get_all_numbers {
-
-        #[test]
-        fn finds_no_numbers() {
-            use super::get_all_numbers;
-            use super::get_bitmasks;
-            use super::get_chars;
-
-            let row = get_chars("......");
-
-            let bitmask = match get_bitmasks(&row) {
-                Ok(result) => result.1,
-                Err(_err) => panic!(),
-            };
-
-            let result = match get_all_numbers(&row, bitmask) {
-                Ok(result) => result,
-                Err(_err) => panic!(),
-            };
-
-            assert!(result.is_none())
-        }
-
-        #[test]
-        fn can_find_a_number() {
-            use super::get_all_numbers;
-            use super::get_bitmasks;
-            use super::get_chars;
-
-            let row = get_chars(".12...");
-
-            let bitmask = match get_bitmasks(&row) {
-                Ok(result) => result.1,
-                Err(_err) => panic!(),
-            };
-
-            let result = match get_all_numbers(&row, bitmask) {
-                Ok(result) => result.unwrap(),
-                Err(_err) => panic!(),
-            };
-
-            assert_eq!(result.len(), 1);
-            assert_eq!(result[0].start, 1);
-            assert_eq!(result[0].stop, 3);
-            assert_eq!(result[0].value, 12);
-        }
-
-        #[test]
-        fn can_find_multiple_numbers() {
-            use super::get_all_numbers;
-            use super::get_bitmasks;
-            use super::get_chars;
-
-            let row = get_chars(".32.623..45");
-
-            let bitmask = match get_bitmasks(&row) {
-                Ok(result) => result.1,
-                Err(_err) => panic!(),
-            };
-
-            let o_result = match get_all_numbers(&row, bitmask) {
-                Ok(result) => result,
-                Err(err) => panic!("error from get_all_numbers: {err}"),
-            };
-
-            let result = match o_result {
-                Some(res) => res,
-                None => panic!("no numbers found"),
-            };
-
-            assert_eq!(result.len(), 3);
-            assert_eq!(
-                result.iter().map(|x| x.value as i32).collect::<Vec<_>>(),
-                vec![32, 623, 45]
-            )
-        }
-    }
-    mod test_get_gear_ratios_from_all_rows {
-
-        #[test]
-        fn returns_correct_gear_ratio_from_example() {
-            use super::get_gear_ratios_from_all_rows;
-            use super::get_chars;
-
-            let row_1 = get_chars("467..114..");
-            let row_2 = get_chars("...*......");
-            let row_3 = get_chars("..35..633.");
-
-            let result = match get_gear_ratios_from_all_rows(&row_1, &row_2, &row_3) {
-                Ok(result) => result,
-                Err(err) => panic!("Error in get_gear_ratios_from_all_rows: {err}"),
-            };
-
-            assert!(result.is_some());
-            assert_eq!(result.unwrap(), vec![16_345 as usize]);
-        }
-
-        #[test]
-        fn returns_correct_gear_ratio_from_second_example() {
-            use super::get_gear_ratios_from_all_rows;
-            use super::get_chars;
-
-            let row_1 = get_chars("......755.");
-            let row_2 = get_chars("...$.*....");
-            let row_3 = get_chars(".664.598..");
-
-            let result = match get_gear_ratios_from_all_rows(&row_1, &row_2, &row_3) {
-                Ok(result) => result,
-                Err(err) => panic!("Error in get_gear_ratios_from_all_rows: {err}"),
-            };
-
-            assert!(result.is_some());
-            assert_eq!(result.unwrap(), vec![451_490 as usize]);
-        }
-    }
-    mod test_get_gear_ratios {
-        #[test]
-        fn test_each_line_of_the_example() {
-            use super::ThreeRowGrid;
-            use super:: get_gear_ratios;
-            use std::fs;
-
-            let input = match fs::read_to_string("src/day_3/test_input_part_1.txt") {
-                Ok(result) => result,
-                Err(err) => panic!("Error in file reading: {err}"),
-            };
-
-            let mut output: Vec<Vec<usize>> = Vec::new();
-            let mut grid = ThreeRowGrid::new(None, None, None);
-
-            for line in input.lines() {
-                match grid.insert_next_row(line) {
-                    Ok(_) => {}
-                    Err(err) => panic!("Error in ThreeGridRow.insert_new_row: {err}"),
-                };
-                output.push(match get_gear_ratios(&grid) {
-                    Ok(opt) => match opt {
-                        Some(vec) => vec,
-                        None => {
-                            let vec: Vec<usize> = Vec::new();
-                            vec
-                        },
-                    },
-                    Err(err) => panic!("Error in get_gear_ratios: {err}"),
-                })
-            }
-
-            match grid.handle_last_row() {
-                Ok(_) => {}
-                Err(err) => panic!("Error in ThreeGridRow.handle_last_row: {err}"),
-            };
-
-            output.push(match get_gear_ratios(&grid) {
-                Ok(result) => match result {
-                    Some(vec) => vec,
-                    None => {
-                        let vec: Vec<usize> = Vec::new();
-                        vec
-                    },
-                },
-                Err(err) => panic!("Error in get_machine_part_numbers: {err}"),
-            });
-        }
+        // a wide character is reported as such rather than as a ragged row
+        let expected = Err(String::from("Non-ASCII byte at row 1, column 1"));
+        assert_eq!(solve_part_1("é..\n"), expected);
+        assert_eq!(solve_part_2("é..\n"), expected);
     }
 }
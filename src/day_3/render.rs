@@ -0,0 +1,248 @@
+use super::rules::SchematicRules;
+use super::schematic::{Cell, Schematic, SchematicError};
+use std::ops::Range;
+
+const RESET: &str = "\x1b[0m";
+const PART_NUMBER: &str = "\x1b[32m";
+const OTHER_NUMBER: &str = "\x1b[31m";
+const SYMBOL: &str = "\x1b[33m";
+const GEAR: &str = "\x1b[1;35m";
+const BLANK: &str = "\x1b[90m";
+const RULER: &str = "\x1b[2m";
+
+/// which part of the schematic to draw, and what to draw around it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderOptions {
+    /// counted from 0; every row if `None`
+    pub rows: Option<Range<usize>>,
+    /// counted from 0; every column if `None`
+    pub columns: Option<Range<usize>>,
+    pub legend: bool,
+    /// input line numbers down the side and column numbers across the top, counted from 1
+    pub ruler: bool,
+}
+
+/// helper function to keep a window on the grid
+fn clip(window: &Option<Range<usize>>, size: usize) -> Range<usize> {
+    match window {
+        Some(window) => window.start.min(size)..window.end.min(size),
+        None => 0..size,
+    }
+}
+
+/// helper function for the lines of column numbers over `columns`, most significant digit first
+fn column_ruler(columns: &Range<usize>, indent: usize) -> Vec<String> {
+    let places = columns.end.max(1).to_string().len();
+    (0..places)
+        .rev()
+        .map(|place| {
+            let scale = 10usize.pow(place as u32);
+            let digits: String = columns
+                .clone()
+                .map(|column| column + 1)
+                .map(
+                    |column| match place == 0 || (column % 10 == 0 && column >= scale) {
+                        true => char::from_digit(((column / scale) % 10) as u32, 10).unwrap_or(' '),
+                        false => ' ',
+                    },
+                )
+                .collect();
+            format!("{RULER}{:indent$}{}{RESET}", "", digits)
+        })
+        .collect()
+}
+
+/// draws the schematic with ANSI colours: part numbers green, numbers that are not parts red, symbols yellow and
+/// gears under `rules` bold magenta, with blanks greyed out
+pub fn render(
+    schematic: &Schematic,
+    rules: &SchematicRules,
+    options: &RenderOptions,
+) -> Result<String, SchematicError> {
+    let is_part: Vec<bool> = schematic
+        .numbers()
+        .iter()
+        .map(|number| !schematic.symbols_adjacent_to(number).is_empty())
+        .collect();
+    let is_gear: Vec<bool> = schematic
+        .symbol_reports(rules)?
+        .iter()
+        .map(|report| report.gear_value.is_some())
+        .collect();
+    let rows = clip(&options.rows, schematic.rows());
+    let columns = clip(&options.columns, schematic.columns());
+    // rows are labelled with the input line they came from, so they match the error messages
+    let label_width = match options.ruler {
+        true => {
+            let last_line = rows.clone().last().map_or(1, |row| schematic.line(row) + 1);
+            last_line.to_string().len() + 1
+        }
+        false => 0,
+    };
+
+    let mut output = String::new();
+    if options.ruler {
+        for line in column_ruler(&columns, label_width) {
+            output.push_str(&line);
+            output.push('\n');
+        }
+    }
+    for row in rows {
+        if options.ruler {
+            output.push_str(&format!(
+                "{RULER}{:>width$} {RESET}",
+                schematic.line(row) + 1,
+                width = label_width - 1
+            ));
+        }
+        let mut colour = RESET;
+        for column in columns.clone() {
+            let (cell_colour, character) = match schematic.cell(row, column) {
+                Some(Cell::Blank(character)) => (BLANK, character),
+                Some(Cell::Digit(index, character)) => match is_part[index] {
+                    true => (PART_NUMBER, character),
+                    false => (OTHER_NUMBER, character),
+                },
+                Some(Cell::Symbol(index)) => match is_gear[index] {
+                    true => (GEAR, schematic.symbols()[index].character),
+                    false => (SYMBOL, schematic.symbols()[index].character),
+                },
+                None => (RESET, ' '),
+            };
+            if cell_colour != colour {
+                // reset first, as a gear's bold would otherwise carry on
+                if colour != RESET {
+                    output.push_str(RESET);
+                }
+                output.push_str(cell_colour);
+                colour = cell_colour;
+            }
+            output.push(character);
+        }
+        output.push_str(RESET);
+        output.push('\n');
+    }
+    if options.legend {
+        output.push_str(&format!(
+            "{PART_NUMBER}123{RESET} part number  {OTHER_NUMBER}123{RESET} not a part  {SYMBOL}#{RESET} symbol  {GEAR}{}{RESET} gear ({} parts, {:?})\n",
+            rules.gear_symbol, rules.gear_parts, rules.aggregation
+        ));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{column_ruler, render, RenderOptions, GEAR, OTHER_NUMBER, PART_NUMBER};
+    use crate::day_3::{rules::SchematicRules, schematic::Schematic};
+
+    /// helper function to drop the colours again
+    fn strip_colours(input: &str) -> String {
+        let mut output = String::new();
+        let mut in_escape = false;
+        for character in input.chars() {
+            match (in_escape, character) {
+                (false, '\x1b') => in_escape = true,
+                (false, character) => output.push(character),
+                (true, 'm') => in_escape = false,
+                (true, _) => {}
+            }
+        }
+        output
+    }
+
+    fn example() -> Schematic {
+        use std::fs;
+
+        let input = match fs::read_to_string("src/day_3/test_input_part_1.txt") {
+            Ok(result) => result,
+            Err(err) => panic!("Error in file reading: {err}"),
+        };
+        match Schematic::parse(&input) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in Schematic::parse: {err}"),
+        }
+    }
+
+    #[test]
+    fn colours_each_kind_of_cell() {
+        let output = match render(
+            &example(),
+            &SchematicRules::default(),
+            &RenderOptions::default(),
+        ) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in render: {err}"),
+        };
+
+        assert!(output.starts_with(&format!("{PART_NUMBER}467")));
+        assert!(output.contains(&format!("{OTHER_NUMBER}114")));
+        assert!(output.contains(&format!("{GEAR}*")));
+        // the star next to 617 only touches one number, so it is not a gear
+        assert!(!output.lines().nth(4).unwrap().contains(GEAR));
+        assert_eq!(
+            strip_colours(&output),
+            std::fs::read_to_string("src/day_3/test_input_part_1.txt").unwrap() + "\n"
+        );
+    }
+
+    #[test]
+    fn crops_with_a_ruler_and_legend() {
+        let options = RenderOptions {
+            rows: Some(7..20),
+            columns: Some(2..7),
+            legend: true,
+            ruler: true,
+        };
+
+        let output = match render(&example(), &SchematicRules::default(), &options) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in render: {err}"),
+        };
+
+        assert_eq!(
+            strip_colours(&output),
+            [
+                "   34567",
+                " 8 ....7",
+                " 9 .$.*.",
+                "10 64.59",
+                "123 part number  123 not a part  # symbol  * gear (2 parts, Product)",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn labels_rows_with_their_input_lines() {
+        let input = format!("467..\n\n...*.\n{}..35.\n", "\n".repeat(7));
+        let schematic = match Schematic::parse(&input) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in Schematic::parse: {err}"),
+        };
+        let options = RenderOptions {
+            ruler: true,
+            ..RenderOptions::default()
+        };
+
+        let output = match render(&schematic, &SchematicRules::default(), &options) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in render: {err}"),
+        };
+
+        assert_eq!(
+            strip_colours(&output),
+            ["   12345", " 1 467..", " 3 ...*.", "11 ..35.", ""].join("\n")
+        );
+    }
+
+    #[test]
+    fn numbers_columns_from_one() {
+        let lines = column_ruler(&(7..12), 0);
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("  1  "));
+        assert!(lines[1].contains("89012"));
+    }
+}
@@ -0,0 +1,186 @@
+use crate::shared::{checked_add, checked_mul, Location, NumberError};
+use std::str::FromStr;
+
+/// how the part numbers attached to a gear combine into its value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregation {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Aggregation {
+    /// an overflow is reported at `location`, the gear being aggregated
+    pub fn apply(
+        &self,
+        values: impl Iterator<Item = usize>,
+        location: &Location,
+    ) -> Result<usize, NumberError> {
+        let mut values = values;
+        match self {
+            Aggregation::Product => {
+                values.try_fold(1usize, |total, value| checked_mul(total, value, location))
+            }
+            Aggregation::Sum => {
+                values.try_fold(0usize, |total, value| checked_add(total, value, location))
+            }
+            Aggregation::Max => Ok(values.max().unwrap_or(0)),
+        }
+    }
+}
+
+impl FromStr for Aggregation {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "product" => Ok(Aggregation::Product),
+            "sum" => Ok(Aggregation::Sum),
+            "max" => Ok(Aggregation::Max),
+            other => Err(format!(
+                "Unknown aggregation `{}`, expected product, sum or max",
+                other
+            )),
+        }
+    }
+}
+
+/// which characters mark a part
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolSet {
+    /// anything that is not a digit or a blank
+    AnyOther,
+    /// only these; any other character that is not a digit or a blank is an error
+    Only(Vec<char>),
+}
+
+/// how to read a schematic, and what makes a gear
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchematicRules {
+    pub blanks: Vec<char>,
+    pub symbols: SymbolSet,
+    pub gear_symbol: char,
+    /// a gear symbol is only a gear when exactly this many numbers touch it
+    pub gear_parts: usize,
+    pub aggregation: Aggregation,
+}
+
+impl Default for SchematicRules {
+    /// the puzzle's rules: `.` is blank, everything else is a symbol, and a gear is a `*` touching exactly two
+    /// numbers, whose ratio is their product
+    fn default() -> Self {
+        SchematicRules {
+            blanks: vec!['.'],
+            symbols: SymbolSet::AnyOther,
+            gear_symbol: '*',
+            gear_parts: 2,
+            aggregation: Aggregation::Product,
+        }
+    }
+}
+
+/// what a character of the schematic is, once the rules are applied
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CharacterKind {
+    Digit(usize),
+    Blank,
+    Symbol,
+    Unknown,
+}
+
+impl SchematicRules {
+    pub fn classify(&self, character: char) -> CharacterKind {
+        if let Some(digit) = character.to_digit(10) {
+            return CharacterKind::Digit(digit as usize);
+        }
+        if self.blanks.contains(&character) {
+            return CharacterKind::Blank;
+        }
+        match &self.symbols {
+            SymbolSet::AnyOther => CharacterKind::Symbol,
+            SymbolSet::Only(symbols) if symbols.contains(&character) => CharacterKind::Symbol,
+            SymbolSet::Only(_) => CharacterKind::Unknown,
+        }
+    }
+}
+
+/// what each ASCII byte is under a set of rules, worked out once up front
+pub(super) struct ByteKinds {
+    kinds: [CharacterKind; 128],
+    pub(super) gear_symbol: Option<u8>,
+}
+
+impl ByteKinds {
+    pub(super) fn new(rules: &SchematicRules) -> ByteKinds {
+        let mut kinds = [CharacterKind::Blank; 128];
+        for (byte, kind) in kinds.iter_mut().enumerate() {
+            *kind = rules.classify(byte as u8 as char);
+        }
+        ByteKinds {
+            kinds,
+            gear_symbol: u8::try_from(rules.gear_symbol)
+                .ok()
+                .filter(|byte| byte.is_ascii()),
+        }
+    }
+
+    pub(super) fn kind(&self, byte: u8) -> CharacterKind {
+        self.kinds[byte as usize]
+    }
+
+    pub(super) fn digit(&self, byte: u8) -> Option<usize> {
+        match self.kind(byte) {
+            CharacterKind::Digit(digit) => Some(digit),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Aggregation, CharacterKind, SchematicRules, SymbolSet};
+    use crate::shared::{Location, NumberError};
+
+    #[test]
+    fn aggregates_and_catches_overflow() {
+        let values = [3, 5, 4];
+        let location = Location::Cell { line: 2, column: 4 };
+
+        assert_eq!(
+            Aggregation::Product.apply(values.into_iter(), &location),
+            Ok(60)
+        );
+        assert_eq!(
+            Aggregation::Sum.apply(values.into_iter(), &location),
+            Ok(12)
+        );
+        assert_eq!(Aggregation::Max.apply(values.into_iter(), &location), Ok(5));
+        assert!(matches!(
+            Aggregation::Product.apply([usize::MAX, 2].into_iter(), &location),
+            Err(NumberError::Overflow {
+                location: Location::Cell { line: 2, column: 4 },
+                ..
+            })
+        ));
+        assert_eq!("sum".parse::<Aggregation>(), Ok(Aggregation::Sum));
+        assert!("mean".parse::<Aggregation>().is_err());
+    }
+
+    #[test]
+    fn classifies_characters() {
+        let rules = SchematicRules {
+            blanks: vec!['.', ' '],
+            symbols: SymbolSet::Only(vec!['*', '#']),
+            ..SchematicRules::default()
+        };
+
+        assert_eq!(rules.classify('7'), CharacterKind::Digit(7));
+        assert_eq!(rules.classify(' '), CharacterKind::Blank);
+        assert_eq!(rules.classify('#'), CharacterKind::Symbol);
+        assert_eq!(rules.classify('$'), CharacterKind::Unknown);
+        assert_eq!(
+            SchematicRules::default().classify('$'),
+            CharacterKind::Symbol
+        );
+    }
+}
@@ -0,0 +1,542 @@
+use super::rules::{CharacterKind, SchematicRules};
+use crate::shared::{push_digit, Location, NumberError};
+use std::fmt;
+use thiserror::Error;
+
+/// a run of digits on one row, covering the columns `start..stop`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Number {
+    pub row: usize,
+    pub start: usize,
+    pub stop: usize,
+    pub value: usize,
+}
+
+/// a character the rules count as a symbol
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Symbol {
+    pub row: usize,
+    pub column: usize,
+    pub character: char,
+}
+
+/// positions are counted from 1 in messages, and from 0 in the fields.
+/// rows are the lines of the input, counting any blank lines that were skipped
+#[derive(Debug, Error, PartialEq)]
+pub enum SchematicError {
+    #[error("Row {} is {found} characters wide, but the schematic is {expected} wide", .row + 1)]
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Unknown character `{character}` at row {}, column {}", .row + 1, .column + 1)]
+    UnknownCharacter {
+        row: usize,
+        column: usize,
+        character: char,
+    },
+
+    #[error("Non-ASCII byte at row {}, column {}", .row + 1, .column + 1)]
+    NonAscii { row: usize, column: usize },
+
+    #[error("Error reading row {}: {message}", .row + 1)]
+    Read { row: usize, message: String },
+
+    /// a number, gear or total that does not fit, located at the number or gear
+    #[error(transparent)]
+    Number(#[from] NumberError),
+}
+
+/// helper function for where a cell is, counted from 1, given its row and column counted from 0
+pub(super) fn cell_location(row: usize, column: usize) -> Location {
+    Location::Cell {
+        line: row + 1,
+        column: column + 1,
+    }
+}
+
+/// a symbol along with every number touching it
+#[derive(Debug, PartialEq)]
+pub struct SymbolReport<'a> {
+    pub symbol: &'a Symbol,
+    pub numbers: Vec<&'a Number>,
+    /// the aggregated part numbers, if the symbol is a gear under the rules used
+    pub gear_value: Option<usize>,
+    /// the line of the input the symbol is on, counted from 0, which is its row unless blank lines were skipped
+    pub line: usize,
+}
+
+/// what is in one cell of the grid, pointing back into `numbers` or `symbols`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Cell {
+    Blank(char),
+    Digit(usize, char),
+    Symbol(usize),
+}
+
+/// the whole engine schematic, parsed once and indexed by position so neighbours can be looked up directly
+#[derive(Debug)]
+pub struct Schematic {
+    rows: usize,
+    columns: usize,
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    /// row by row
+    cells: Vec<Cell>,
+    /// the line of the input each row was read from, counted from 0
+    lines: Vec<usize>,
+}
+
+impl Schematic {
+    /// parses with the puzzle's rules
+    #[cfg(test)]
+    pub fn parse(input: &str) -> Result<Schematic, SchematicError> {
+        Schematic::parse_with(input, &SchematicRules::default())
+    }
+
+    pub fn parse_with(input: &str, rules: &SchematicRules) -> Result<Schematic, SchematicError> {
+        // blank lines are skipped, but each row remembers the line it came from for messages
+        let lines: Vec<(usize, &str)> = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .collect();
+        let columns = lines.first().map_or(0, |(_, line)| line.len());
+        let mut schematic = Schematic {
+            rows: lines.len(),
+            columns,
+            numbers: vec![],
+            symbols: vec![],
+            cells: Vec::with_capacity(lines.len() * columns),
+            lines: lines.iter().map(|(line_index, _)| *line_index).collect(),
+        };
+
+        for (row, (line_index, line)) in lines.iter().enumerate() {
+            // the same rule as the other solvers: ASCII only, checked before the width
+            if let Some(column) = line.bytes().position(|byte| !byte.is_ascii()) {
+                return Err(SchematicError::NonAscii {
+                    row: *line_index,
+                    column,
+                });
+            }
+            if line.len() != columns {
+                return Err(SchematicError::RaggedRow {
+                    row: *line_index,
+                    expected: columns,
+                    found: line.len(),
+                });
+            }
+            // the number being read, if the previous character was a digit
+            let mut current: Option<Number> = None;
+            for (column, character) in line.chars().enumerate() {
+                match rules.classify(character) {
+                    CharacterKind::Digit(digit) => {
+                        let number = current.get_or_insert(Number {
+                            row,
+                            start: column,
+                            stop: column,
+                            value: 0,
+                        });
+                        number.value = push_digit(
+                            number.value,
+                            digit as u8,
+                            &cell_location(*line_index, number.start),
+                        )?;
+                        number.stop = column + 1;
+                        schematic
+                            .cells
+                            .push(Cell::Digit(schematic.numbers.len(), character));
+                    }
+                    kind => {
+                        if let Some(number) = current.take() {
+                            schematic.numbers.push(number);
+                        }
+                        match kind {
+                            CharacterKind::Symbol => {
+                                schematic.cells.push(Cell::Symbol(schematic.symbols.len()));
+                                schematic.symbols.push(Symbol {
+                                    row,
+                                    column,
+                                    character,
+                                });
+                            }
+                            CharacterKind::Unknown => {
+                                return Err(SchematicError::UnknownCharacter {
+                                    row: *line_index,
+                                    column,
+                                    character,
+                                })
+                            }
+                            _ => schematic.cells.push(Cell::Blank(character)),
+                        }
+                    }
+                }
+            }
+            if let Some(number) = current {
+                schematic.numbers.push(number);
+            }
+        }
+
+        Ok(schematic)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// the line of the input `row` was read from, counted from 0
+    pub fn line(&self, row: usize) -> usize {
+        self.lines[row]
+    }
+
+    /// every number, in reading order
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    /// every symbol, in reading order
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// what is at (`row`, `column`), if it is on the grid
+    pub(super) fn cell(&self, row: usize, column: usize) -> Option<Cell> {
+        match row < self.rows && column < self.columns {
+            true => Some(self.cells[row * self.columns + column]),
+            false => None,
+        }
+    }
+
+    /// helper function for every cell in the block `rows` by `columns`, clipped to the grid
+    fn cells_in(
+        &self,
+        rows: (usize, usize),
+        columns: (usize, usize),
+    ) -> impl Iterator<Item = Cell> + '_ {
+        (rows.0.saturating_sub(1)..=rows.1).flat_map(move |row| {
+            (columns.0.saturating_sub(1)..=columns.1)
+                .filter_map(move |column| self.cell(row, column))
+        })
+    }
+
+    /// the numbers touching (`row`, `column`), including diagonally, each once and in reading order
+    pub fn numbers_adjacent_to(&self, row: usize, column: usize) -> Vec<&Number> {
+        let mut indices: Vec<usize> = self
+            .cells_in((row, row + 1), (column, column + 1))
+            .filter_map(|cell| match cell {
+                Cell::Digit(index, _) => Some(index),
+                _ => None,
+            })
+            .collect();
+        indices.sort();
+        indices.dedup();
+        indices
+            .into_iter()
+            .map(|index| &self.numbers[index])
+            .collect()
+    }
+
+    /// the symbols touching any digit of `number`, including diagonally, in reading order
+    pub fn symbols_adjacent_to(&self, number: &Number) -> Vec<&Symbol> {
+        self.cells_in((number.row, number.row + 1), (number.start, number.stop))
+            .filter_map(|cell| match cell {
+                Cell::Symbol(index) => Some(&self.symbols[index]),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// the numbers next to at least one symbol
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .filter(|number| !self.symbols_adjacent_to(number).is_empty())
+    }
+
+    /// every symbol with the numbers touching it, and its value if it is a gear under `rules`
+    pub fn symbol_reports(
+        &self,
+        rules: &SchematicRules,
+    ) -> Result<Vec<SymbolReport<'_>>, SchematicError> {
+        self.symbols
+            .iter()
+            .map(|symbol| {
+                let numbers = self.numbers_adjacent_to(symbol.row, symbol.column);
+                let is_gear =
+                    symbol.character == rules.gear_symbol && numbers.len() == rules.gear_parts;
+                let gear_value = match is_gear {
+                    true => Some(rules.aggregation.apply(
+                        numbers.iter().map(|number| number.value),
+                        &cell_location(self.lines[symbol.row], symbol.column),
+                    )?),
+                    false => None,
+                };
+                Ok(SymbolReport {
+                    symbol,
+                    numbers,
+                    gear_value,
+                    line: self.lines[symbol.row],
+                })
+            })
+            .collect()
+    }
+
+    /// the value of every gear under `rules`, in reading order
+    pub fn gear_values(&self, rules: &SchematicRules) -> Result<Vec<usize>, SchematicError> {
+        Ok(self
+            .symbol_reports(rules)?
+            .iter()
+            .filter_map(|report| report.gear_value)
+            .collect())
+    }
+}
+
+/// writes the symbol, where it is, and what touches it
+impl fmt::Display for SymbolReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let numbers = self
+            .numbers
+            .iter()
+            .map(|number| number.value.to_string())
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "`{}` at row {}, column {}: {}",
+            self.symbol.character,
+            self.line + 1,
+            self.symbol.column + 1,
+            match numbers.is_empty() {
+                true => String::from("no numbers"),
+                false => numbers.join(", "),
+            }
+        )?;
+        if let Some(gear_value) = self.gear_value {
+            write!(f, " (gear, {})", gear_value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Number, Schematic, SchematicError, Symbol};
+    use crate::day_3::rules::{Aggregation, SchematicRules, SymbolSet};
+
+    fn example() -> Schematic {
+        use std::fs;
+
+        let input = match fs::read_to_string("src/day_3/test_input_part_1.txt") {
+            Ok(result) => result,
+            Err(err) => panic!("Error in file reading: {err}"),
+        };
+        match Schematic::parse(&input) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in Schematic::parse: {err}"),
+        }
+    }
+
+    #[test]
+    fn indexes_numbers_and_symbols() {
+        let schematic = example();
+
+        assert_eq!((schematic.rows(), schematic.columns()), (10, 10));
+        assert_eq!(schematic.numbers().len(), 10);
+        assert_eq!(
+            schematic.numbers()[1],
+            Number {
+                row: 0,
+                start: 5,
+                stop: 8,
+                value: 114
+            }
+        );
+        assert_eq!(
+            schematic.symbols()[0],
+            Symbol {
+                row: 1,
+                column: 3,
+                character: '*'
+            }
+        );
+    }
+
+    #[test]
+    fn answers_neighbour_queries() {
+        let schematic = example();
+
+        let values: Vec<usize> = schematic
+            .numbers_adjacent_to(1, 3)
+            .iter()
+            .map(|number| number.value)
+            .collect();
+        assert_eq!(values, vec![467, 35]);
+        assert!(schematic.numbers_adjacent_to(9, 9).is_empty());
+
+        let number_58 = schematic.numbers()[5];
+        assert_eq!(number_58.value, 58);
+        assert!(schematic.symbols_adjacent_to(&number_58).is_empty());
+        let number_617 = schematic.numbers()[4];
+        assert_eq!(
+            schematic.symbols_adjacent_to(&number_617),
+            vec![&Symbol {
+                row: 4,
+                column: 3,
+                character: '*'
+            }]
+        );
+    }
+
+    #[test]
+    fn finds_part_numbers_and_gears() {
+        let schematic = example();
+
+        let part_numbers: Vec<usize> = schematic
+            .part_numbers()
+            .map(|number| number.value)
+            .collect();
+        assert_eq!(part_numbers, vec![467, 35, 633, 617, 592, 755, 664, 598]);
+        assert_eq!(
+            schematic.gear_values(&SchematicRules::default()),
+            Ok(vec![16_345, 451_490])
+        );
+    }
+
+    #[test]
+    fn finds_part_numbers_across_rows() {
+        let schematic = match Schematic::parse(
+            ".679.....662....71............................805..........862.680...................................................................687....\n\
+             ............*....-..811..........846..855......*.............*..$........230.92@............................=.....................92........\n\
+             ..........360..........#....664.....=.*...881...677...934.780.......426.*..........8......654.....*959.....539..........21.........*........\n\
+             .....................+.........*......379..*.........*.........=.........969........*........*.976..............872....*....../....579......",
+        ) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in Schematic::parse: {err}"),
+        };
+
+        let part_numbers: Vec<usize> = schematic
+            .part_numbers()
+            .filter(|number| number.row == 1 || number.row == 2)
+            .map(|number| number.value)
+            .collect();
+        assert_eq!(
+            part_numbers,
+            vec![811, 846, 855, 230, 92, 92, 360, 664, 881, 677, 934, 780, 8, 654, 959, 539, 21]
+        );
+    }
+
+    #[test]
+    fn handles_numbers_at_the_edges() {
+        let schematic = match Schematic::parse("12.\n..*\n3..") {
+            Ok(result) => result,
+            Err(err) => panic!("Error in Schematic::parse: {err}"),
+        };
+
+        let part_numbers: Vec<usize> = schematic
+            .part_numbers()
+            .map(|number| number.value)
+            .collect();
+        assert_eq!(part_numbers, vec![12]);
+        assert_eq!(schematic.numbers_adjacent_to(0, 0).len(), 1);
+    }
+
+    #[test]
+    fn rejects_bad_grids() {
+        assert_eq!(
+            Schematic::parse("...\n..\n").unwrap_err(),
+            SchematicError::RaggedRow {
+                row: 1,
+                expected: 3,
+                found: 2
+            }
+        );
+        let err = Schematic::parse(".99999999999999999999999").unwrap_err();
+        assert!(matches!(err, SchematicError::Number(_)));
+        assert_eq!(err.to_string(), "Overflow multiplying at line 1, column 2");
+    }
+
+    #[test]
+    fn locates_by_line_past_blank_lines() {
+        assert_eq!(
+            Schematic::parse("...\n\n..\n").unwrap_err(),
+            SchematicError::RaggedRow {
+                row: 2,
+                expected: 3,
+                found: 2
+            }
+        );
+
+        // the blank line is skipped, so the rows still touch
+        let schematic = match Schematic::parse("12.\n\n..*\n") {
+            Ok(result) => result,
+            Err(err) => panic!("Error in Schematic::parse: {err}"),
+        };
+        let reports = schematic
+            .symbol_reports(&SchematicRules::default())
+            .unwrap();
+        assert_eq!(reports[0].to_string(), "`*` at row 3, column 3: 12");
+    }
+
+    #[test]
+    fn follows_configured_gear_rules() {
+        let input = "1.2.3\n.*.*.\n4...5";
+        let schematic = match Schematic::parse(input) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in Schematic::parse: {err}"),
+        };
+
+        // the first star touches 1, 2 and 4, the second 2, 3 and 5, so neither is a two-part gear
+        assert_eq!(
+            schematic.gear_values(&SchematicRules::default()),
+            Ok(vec![])
+        );
+        let three_parts = SchematicRules {
+            gear_parts: 3,
+            aggregation: Aggregation::Sum,
+            ..SchematicRules::default()
+        };
+        assert_eq!(schematic.gear_values(&three_parts), Ok(vec![7, 10]));
+        let reports = schematic
+            .symbol_reports(&SchematicRules {
+                aggregation: Aggregation::Max,
+                ..three_parts
+            })
+            .unwrap();
+        assert_eq!(
+            reports[1].to_string(),
+            "`*` at row 2, column 4: 2, 3, 5 (gear, 5)"
+        );
+    }
+
+    #[test]
+    fn follows_configured_characters() {
+        let rules = SchematicRules {
+            blanks: vec!['.', '$'],
+            symbols: SymbolSet::Only(vec!['#', '*']),
+            ..SchematicRules::default()
+        };
+
+        let schematic = match Schematic::parse_with("12$..\n...#4", &rules) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in Schematic::parse_with: {err}"),
+        };
+        let part_numbers: Vec<usize> = schematic
+            .part_numbers()
+            .map(|number| number.value)
+            .collect();
+        assert_eq!(part_numbers, vec![4]);
+        assert_eq!(
+            Schematic::parse_with("1+2", &rules).unwrap_err(),
+            SchematicError::UnknownCharacter {
+                row: 0,
+                column: 1,
+                character: '+'
+            }
+        );
+    }
+}
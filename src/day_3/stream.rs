@@ -0,0 +1,364 @@
+use super::rules::{ByteKinds, CharacterKind, SchematicRules};
+use super::schematic::{cell_location, SchematicError};
+use crate::shared::{checked_add, push_digit, DayResult, Location};
+use std::io::BufRead;
+
+/// three consecutive rows of the schematic, as bytes, reusing the same buffers all the way down
+struct RowWindow {
+    above: Option<Vec<u8>>,
+    middle: Option<Vec<u8>>,
+    below: Option<Vec<u8>>,
+    /// the line of the input each of `above`, `middle` and `below` was read from, counted from 0
+    lines: [usize; 3],
+}
+
+impl RowWindow {
+    fn rows(&self) -> [Option<&[u8]>; 3] {
+        [
+            self.above.as_deref(),
+            self.middle.as_deref(),
+            self.below.as_deref(),
+        ]
+    }
+}
+
+/// helper function for the number covering `column` of `row`, as `(start, stop, value)`
+fn number_at(
+    row: &[u8],
+    column: usize,
+    byte_kinds: &ByteKinds,
+    row_index: usize,
+) -> Result<Option<(usize, usize, usize)>, SchematicError> {
+    if byte_kinds.digit(row[column]).is_none() {
+        return Ok(None);
+    }
+    let mut start = column;
+    while start > 0 && byte_kinds.digit(row[start - 1]).is_some() {
+        start -= 1;
+    }
+    let mut stop = start;
+    let mut value: usize = 0;
+    let location = cell_location(row_index, start);
+    while let Some(digit) = row.get(stop).and_then(|byte| byte_kinds.digit(*byte)) {
+        value = push_digit(value, digit as u8, &location)?;
+        stop += 1;
+    }
+    Ok(Some((start, stop, value)))
+}
+
+/// helper function for the part numbers and gear values whose number or gear is on the middle row of `window`
+fn scan_middle_row(
+    window: &RowWindow,
+    byte_kinds: &ByteKinds,
+    rules: &SchematicRules,
+    touching: &mut Vec<usize>,
+) -> Result<(usize, usize), SchematicError> {
+    let Some(middle) = window.middle.as_deref() else {
+        return Ok((0, 0));
+    };
+    let rows = window.rows();
+    let mut part_numbers: usize = 0;
+    let mut gear_values: usize = 0;
+
+    let mut column = 0;
+    while column < middle.len() {
+        let Some((start, stop, value)) = number_at(middle, column, byte_kinds, window.lines[1])?
+        else {
+            column += 1;
+            continue;
+        };
+        let has_symbol = rows.iter().flatten().any(|row| {
+            row[start.saturating_sub(1)..(stop + 1).min(row.len())]
+                .iter()
+                .any(|byte| byte_kinds.kind(*byte) == CharacterKind::Symbol)
+        });
+        if has_symbol {
+            part_numbers =
+                checked_add(part_numbers, value, &cell_location(window.lines[1], start))?;
+        }
+        column = stop;
+    }
+
+    let Some(gear_symbol) = byte_kinds.gear_symbol else {
+        return Ok((part_numbers, 0));
+    };
+    for (column, _) in middle
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == gear_symbol)
+        .filter(|(_, byte)| byte_kinds.kind(**byte) == CharacterKind::Symbol)
+    {
+        touching.clear();
+        for (offset, row) in rows
+            .iter()
+            .enumerate()
+            .filter_map(|(offset, row)| row.map(|row| (offset, row)))
+        {
+            let mut neighbour = column.saturating_sub(1);
+            while neighbour <= column + 1 && neighbour < row.len() {
+                match number_at(row, neighbour, byte_kinds, window.lines[offset])? {
+                    Some((_, stop, value)) => {
+                        touching.push(value);
+                        neighbour = stop;
+                    }
+                    None => neighbour += 1,
+                }
+            }
+        }
+        if touching.len() == rules.gear_parts {
+            let location = cell_location(window.lines[1], column);
+            let value = rules
+                .aggregation
+                .apply(touching.iter().copied(), &location)?;
+            gear_values = checked_add(gear_values, value, &location)?;
+        }
+    }
+
+    Ok((part_numbers, gear_values))
+}
+
+/// solves both parts while reading the schematic from `reader` one row at a time, holding no more than three rows.
+/// rows are read as bytes, so the schematic must be ASCII
+pub fn solve_streaming<R: BufRead>(
+    mut reader: R,
+    rules: &SchematicRules,
+) -> Result<DayResult, SchematicError> {
+    let byte_kinds = ByteKinds::new(rules);
+    let mut window = RowWindow {
+        above: None,
+        middle: None,
+        below: None,
+        lines: [0; 3],
+    };
+    let mut touching: Vec<usize> = vec![];
+    let (mut part_1, mut part_2) = (0, 0);
+    let mut width: Option<usize> = None;
+    // every line of the input, blank or not, counted from 0
+    let mut row = 0;
+    let mut line: Vec<u8> = vec![];
+
+    loop {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|err| SchematicError::Read {
+                row,
+                message: err.to_string(),
+            })?;
+        while matches!(line.last(), Some(b'\n' | b'\r')) {
+            line.pop();
+        }
+        let next = match (read, line.is_empty()) {
+            (0, _) => None,
+            (_, true) => {
+                row += 1;
+                continue;
+            }
+            (_, false) => {
+                if let Some(column) = line.iter().position(|byte| !byte.is_ascii()) {
+                    return Err(SchematicError::NonAscii { row, column });
+                }
+                let expected = *width.get_or_insert(line.len());
+                if line.len() != expected {
+                    return Err(SchematicError::RaggedRow {
+                        row,
+                        expected,
+                        found: line.len(),
+                    });
+                }
+                if let Some((index, byte)) = line
+                    .iter()
+                    .enumerate()
+                    .find(|(_, byte)| byte_kinds.kind(**byte) == CharacterKind::Unknown)
+                {
+                    return Err(SchematicError::UnknownCharacter {
+                        row,
+                        column: index,
+                        character: *byte as char,
+                    });
+                }
+                // the row dropping out of the window lends its buffer to the next read
+                let spare = window.above.take().unwrap_or_default();
+                Some(std::mem::replace(&mut line, spare))
+            }
+        };
+        let finished = next.is_none();
+
+        window.above = window.middle.take();
+        window.middle = window.below.take();
+        window.below = next;
+        window.lines = [window.lines[1], window.lines[2], row];
+        if window.middle.is_some() {
+            let (part_numbers, gear_values) =
+                scan_middle_row(&window, &byte_kinds, rules, &mut touching)?;
+            let location = Location::Line(window.lines[1] + 1);
+            part_1 = checked_add(part_1, part_numbers, &location)?;
+            part_2 = checked_add(part_2, gear_values, &location)?;
+        }
+        if finished {
+            return Ok(DayResult {
+                part_1: part_1.into(),
+                part_2: part_2.into(),
+            });
+        }
+        row += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::solve_streaming;
+    use crate::day_3::{
+        rules::{Aggregation, SchematicRules},
+        schematic::{Schematic, SchematicError},
+    };
+    use std::io::{self, BufReader, Read};
+
+    /// the example schematic stacked `copies` times, written out a row at a time as it is read
+    struct RepeatedExample {
+        rows: Vec<Vec<u8>>,
+        copies: usize,
+        next_row: usize,
+        offset: usize,
+    }
+
+    impl Read for RepeatedExample {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            if self.next_row == self.rows.len() * self.copies {
+                return Ok(0);
+            }
+            let row = &self.rows[self.next_row % self.rows.len()];
+            let length = buffer.len().min(row.len() - self.offset);
+            buffer[..length].copy_from_slice(&row[self.offset..self.offset + length]);
+            self.offset += length;
+            if self.offset == row.len() {
+                self.offset = 0;
+                self.next_row += 1;
+            }
+            Ok(length)
+        }
+    }
+
+    /// helper function for a made-up schematic of `rows` rows, dense with numbers and symbols
+    fn generated_schematic(rows: usize, columns: usize, seed: u64) -> String {
+        let mut state = seed;
+        let mut output = String::new();
+        for _ in 0..rows {
+            for _ in 0..columns {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                output.push(match (state >> 32) % 12 {
+                    0..=4 => '.',
+                    5 => '*',
+                    6 => '#',
+                    digit => char::from_digit(digit as u32, 10).unwrap_or('.'),
+                });
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    #[test]
+    fn matches_the_whole_schematic() {
+        for (seed, rules) in [
+            (1, SchematicRules::default()),
+            (
+                2,
+                SchematicRules {
+                    gear_parts: 3,
+                    aggregation: Aggregation::Sum,
+                    ..SchematicRules::default()
+                },
+            ),
+            (
+                3,
+                SchematicRules {
+                    gear_symbol: '#',
+                    gear_parts: 1,
+                    aggregation: Aggregation::Max,
+                    ..SchematicRules::default()
+                },
+            ),
+        ] {
+            let input = generated_schematic(300, 40, 0x9e37_79b9_7f4a_7c15 ^ seed);
+            let schematic = match Schematic::parse_with(&input, &rules) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in Schematic::parse_with: {err}"),
+            };
+            let expected_part_1: usize = schematic.part_numbers().map(|number| number.value).sum();
+            let expected_part_2: usize = schematic.gear_values(&rules).unwrap().iter().sum();
+
+            let result = match solve_streaming(input.as_bytes(), &rules) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in solve_streaming: {err}"),
+            };
+
+            assert_eq!(result.part_1, expected_part_1);
+            assert_eq!(result.part_2, expected_part_2);
+        }
+    }
+
+    #[test]
+    fn streams_millions_of_rows() {
+        use std::fs;
+
+        let example = match fs::read_to_string("src/day_3/test_input_part_1.txt") {
+            Ok(result) => result,
+            Err(err) => panic!("Error in file reading: {err}"),
+        };
+        let copies = 200_000;
+        let reader = RepeatedExample {
+            rows: example
+                .lines()
+                .map(|line| format!("{}\n", line).into_bytes())
+                .collect(),
+            copies,
+            next_row: 0,
+            offset: 0,
+        };
+
+        let result = match solve_streaming(BufReader::new(reader), &SchematicRules::default()) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in solve_streaming: {err}"),
+        };
+
+        // 2 million rows, and no copy of the example touches the next
+        assert_eq!(result.part_1, 4361 * copies);
+        assert_eq!(result.part_2, 467835 * copies);
+    }
+
+    #[test]
+    fn reports_bad_rows() {
+        let rules = SchematicRules::default();
+
+        assert_eq!(
+            solve_streaming("...\n..\n".as_bytes(), &rules).err(),
+            Some(SchematicError::RaggedRow {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            solve_streaming(".é.\n".as_bytes(), &rules).err(),
+            Some(SchematicError::NonAscii { row: 0, column: 1 })
+        );
+        assert_eq!(
+            solve_streaming("...\n\n..\n".as_bytes(), &rules).err(),
+            Some(SchematicError::RaggedRow {
+                row: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+        let overflowing = format!("{}\n\n*{}\n", ".".repeat(24), "9".repeat(23));
+        assert_eq!(
+            solve_streaming(overflowing.as_bytes(), &rules)
+                .err()
+                .map(|err| err.to_string()),
+            Some(String::from("Overflow multiplying at line 3, column 2"))
+        );
+    }
+}
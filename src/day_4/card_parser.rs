@@ -1,12 +1,28 @@
 use regex::Regex;
+use std::io::BufRead;
 use thiserror::Error;
-use std::num::ParseIntError;
 
-#[derive(Debug)]
+use super::number_set::NumberSet;
+use crate::shared::{parse_number, Location, NumberError};
+
+#[derive(Debug, Default)]
 pub struct CardInfo {
     pub card_id: usize,
     pub winning_numbers: Vec<usize>,
     pub card_numbers: Vec<usize>,
+    /// `winning_numbers` again, so `matches` can look each card number up
+    winning_number_set: NumberSet,
+}
+
+impl CardInfo {
+    /// how many of the card's numbers are winning numbers.
+    /// a number the card shows twice counts twice; the parser makes sure no winning number repeats
+    pub fn matches(&self) -> usize {
+        self.card_numbers
+            .iter()
+            .filter(|number| self.winning_number_set.contains(**number))
+            .count()
+    }
 }
 
 #[derive(Debug, Error)]
@@ -14,63 +30,291 @@ pub enum CardParseError {
     #[error("Failed to compile regex: {0}")]
     RegexError(#[from] regex::Error),
 
-    #[error("Failed to capture card ID")]
-    CardIdCaptureError,
+    #[error("Failed to capture card ID at line {line}, column {column}")]
+    CardIdCaptureError { line: usize, column: usize },
+
+    #[error("Failed to capture winning numbers at line {line}, column {column}")]
+    WinningNumbersCaptureError { line: usize, column: usize },
 
-    #[error("Failed to parse card ID: {0}")]
-    CardIdParseError(#[from] ParseIntError),
+    #[error("Failed to capture card numbers at line {line}, column {column}")]
+    CardNumbersCaptureError { line: usize, column: usize },
 
-    #[error("Failed to capture winning numbers")]
-    WinningNumbersCaptureError,
+    #[error("Winning number {number} appears again at line {line}, column {column}")]
+    DuplicateWinningNumber {
+        number: usize,
+        line: usize,
+        column: usize,
+    },
 
-    #[error("Failed to parse winning numbers: {0}")]
-    WinningNumbersParseError(ParseIntError),
+    /// a number too large for a `usize`, at a `Location::Cell`
+    #[error("Failed to parse card: {0}")]
+    NumberError(#[from] NumberError),
 
-    #[error("Failed to capture card numbers")]
-    CardNumbersCaptureError,
+    #[error("Error reading line {line}: {message}")]
+    Read { line: usize, message: String },
+}
+
+/// helper function to turn a byte offset into `line` into a column, counted from 1
+fn column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+/// helper function to find the byte offset at which `line` stops looking like `Card <id>:`
+fn card_id_failure(line: &str) -> usize {
+    let rest = match line.strip_prefix("Card") {
+        Some(rest) => rest,
+        None => return 0,
+    };
+    let id = rest.trim_start();
+    if id.len() == rest.len() {
+        return line.len() - rest.len();
+    }
+    line.len() - id.trim_start_matches(|c: char| c.is_ascii_digit()).len()
+}
 
-    #[error("Failed to parse card numbers: {0}")]
-    CardNumbersParseError(ParseIntError),
+/// parses scratchcards with its patterns compiled once, up front
+pub struct CardParser {
+    /// `Card <id>:` at the start of a line
+    card_id: Regex,
+    number: Regex,
 }
 
-pub fn parse_card(input: &str) -> Result<CardInfo, CardParseError> {
-    let pattern = r"Card\s+(\d+): ([\d\s]+) \| ([\d\s]+)";
-    let re = Regex::new(pattern)?;
+impl CardParser {
+    pub fn new() -> Result<CardParser, CardParseError> {
+        Ok(CardParser {
+            card_id: Regex::new(r"^Card\s+(\d+):")?,
+            number: Regex::new(r"\d+")?,
+        })
+    }
 
-    let captures = re.captures(input).ok_or(CardParseError::CardIdCaptureError)?;
+    /// reads the card on `line`, counted from 1, into `card`, reusing the space its numbers already take up
+    pub fn parse_into(
+        &self,
+        line: &str,
+        line_number: usize,
+        card: &mut CardInfo,
+    ) -> Result<(), CardParseError> {
+        let at = |offset: usize| Location::Cell {
+            line: line_number,
+            column: column(line, offset),
+        };
 
-    let card_id = captures.get(1)
-        .ok_or(CardParseError::CardIdCaptureError)?
-        .as_str()
-        .parse::<usize>()?;
+        let captures =
+            self.card_id
+                .captures(line)
+                .ok_or_else(|| CardParseError::CardIdCaptureError {
+                    line: line_number,
+                    column: column(line, card_id_failure(line)),
+                })?;
+        let card_id = captures.get(1).expect("the pattern captures the id");
+        card.card_id = parse_number(card_id.as_str(), at(card_id.start()))?;
 
-    let winning_numbers_str = captures.get(2).ok_or(CardParseError::WinningNumbersCaptureError)?.as_str();
-    let winning_numbers = winning_numbers_str
-        .split_whitespace()
-        .map(|s| s.parse::<usize>().map_err(CardParseError::WinningNumbersParseError))
-        .collect::<Result<Vec<usize>, CardParseError>>()?;
+        let not_a_number = |c: char| !(c.is_ascii_digit() || c.is_whitespace());
+        let winning_start = card_id.end() + 1;
+        let separator = match line[winning_start..].find(not_a_number) {
+            Some(offset) if line[winning_start + offset..].starts_with('|') => {
+                winning_start + offset
+            }
+            found => {
+                return Err(CardParseError::WinningNumbersCaptureError {
+                    line: line_number,
+                    column: column(
+                        line,
+                        found.map_or(line.len(), |offset| winning_start + offset),
+                    ),
+                })
+            }
+        };
+        if let Some(offset) = line[separator + 1..].find(not_a_number) {
+            return Err(CardParseError::CardNumbersCaptureError {
+                line: line_number,
+                column: column(line, separator + 1 + offset),
+            });
+        }
 
-    let card_numbers_str = captures.get(3).ok_or(CardParseError::CardNumbersCaptureError)?.as_str();
-    let card_numbers = card_numbers_str
-        .split_whitespace()
-        .map(|s| s.parse::<usize>().map_err(CardParseError::CardNumbersParseError))
-        .collect::<Result<Vec<usize>, CardParseError>>()?;
+        card.winning_numbers.clear();
+        card.winning_number_set.clear();
+        for number in self.number.find_iter(&line[winning_start..separator]) {
+            let offset = winning_start + number.start();
+            let value = parse_number(number.as_str(), at(offset))?;
+            if !card.winning_number_set.insert(value) {
+                return Err(CardParseError::DuplicateWinningNumber {
+                    number: value,
+                    line: line_number,
+                    column: column(line, offset),
+                });
+            }
+            card.winning_numbers.push(value);
+        }
+        card.card_numbers.clear();
+        for number in self.number.find_iter(&line[separator + 1..]) {
+            let offset = separator + 1 + number.start();
+            card.card_numbers
+                .push(parse_number(number.as_str(), at(offset))?);
+        }
+        Ok(())
+    }
+}
 
-    Ok(CardInfo { card_id, winning_numbers, card_numbers })
+/// reads cards one line at a time from `reader`, filling the same line buffer and the same `CardInfo` for every card,
+/// so nothing is allocated per card once the first few have been read
+pub struct CardReader<R> {
+    reader: R,
+    parser: CardParser,
+    line: String,
+    /// counted from 1
+    line_number: usize,
+    card: CardInfo,
 }
 
+impl<R: BufRead> CardReader<R> {
+    pub fn new(reader: R) -> Result<CardReader<R>, CardParseError> {
+        Ok(CardReader {
+            reader,
+            parser: CardParser::new()?,
+            line: String::new(),
+            line_number: 0,
+            card: CardInfo::default(),
+        })
+    }
+
+    /// the line the last card was read from, counted from 1
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// the next card, or `None` once the input runs out. blank lines are skipped.
+    /// this is not an `Iterator`, since each card borrows from the reader until the next is read
+    pub fn next_card(&mut self) -> Option<Result<&CardInfo, CardParseError>> {
+        loop {
+            self.line.clear();
+            self.line_number += 1;
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) if self.line.trim().is_empty() => continue,
+                Ok(_) => break,
+                Err(err) => {
+                    return Some(Err(CardParseError::Read {
+                        line: self.line_number,
+                        message: err.to_string(),
+                    }))
+                }
+            }
+        }
+        let parsed = self
+            .parser
+            .parse_into(self.line.trim_end(), self.line_number, &mut self.card);
+        Some(parsed.map(|_| &self.card))
+    }
+}
+
+#[cfg(test)]
 mod tests {
 
     #[test]
     fn test_parse_card() {
-        use super::parse_card;
+        use super::{CardInfo, CardParser};
 
         let input = "Card   1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
-        let result = parse_card(input);
+        let parser = CardParser::new().unwrap();
+        let mut card_info = CardInfo::default();
+        let result = parser.parse_into(input, 1, &mut card_info);
         assert!(result.is_ok());
-        let card_info = result.unwrap();
         assert_eq!(card_info.card_id, 1);
         assert_eq!(card_info.winning_numbers, vec![41, 48, 83, 86, 17]);
         assert_eq!(card_info.card_numbers, vec![83, 86, 6, 31, 17, 9, 48, 53]);
+        assert_eq!(card_info.matches(), 4);
+    }
+
+    #[test]
+    fn test_matches_large_numbers() {
+        use super::{CardInfo, CardParser};
+
+        let parser = CardParser::new().unwrap();
+        let mut card = CardInfo::default();
+        parser
+            .parse_into("Card 1: 5 200 1000 127 | 127 1000 5 5 6", 1, &mut card)
+            .unwrap();
+        assert_eq!(card.matches(), 4);
+    }
+
+    #[test]
+    fn test_rejects_repeated_winning_numbers() {
+        use super::{CardInfo, CardParser};
+
+        let result = CardParser::new().unwrap().parse_into(
+            "Card 1: 5 200 5 | 5 6",
+            3,
+            &mut CardInfo::default(),
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Winning number 5 appears again at line 3, column 15"
+        );
+    }
+
+    #[test]
+    fn test_parse_card_too_large() {
+        use super::{CardInfo, CardParseError, CardParser};
+        use crate::shared::{Location, NumberError};
+
+        let input = "Card 2: 41 48 | 83 99999999999999999999";
+        let result = CardParser::new()
+            .unwrap()
+            .parse_into(input, 7, &mut CardInfo::default());
+        match result {
+            Err(CardParseError::NumberError(NumberError::TooLarge { text, location })) => {
+                assert_eq!(text, "99999999999999999999");
+                assert_eq!(
+                    location,
+                    Location::Cell {
+                        line: 7,
+                        column: 20
+                    }
+                );
+            }
+            other => panic!("Expected a number too large, got {:?}", other),
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors_name_line_and_column() {
+        use super::CardReader;
+
+        let input =
+            "Card 1: 1 2 | 3 4\n\nCard 3 1 2 | 3\nCard 4: 1 x | 3\nCard 5: 1 2 | 3 ?\nCard 6: 1 2";
+        let mut cards = CardReader::new(input.as_bytes()).unwrap();
+
+        assert_eq!(cards.next_card().unwrap().unwrap().card_id, 1);
+        let mut errors = vec![];
+        while let Some(card) = cards.next_card() {
+            errors.push(card.unwrap_err().to_string());
+        }
+        assert_eq!(
+            errors,
+            vec![
+                "Failed to capture card ID at line 3, column 7",
+                "Failed to capture winning numbers at line 4, column 11",
+                "Failed to capture card numbers at line 5, column 17",
+                "Failed to capture winning numbers at line 6, column 12",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reader_reuses_one_card() {
+        use super::CardReader;
+
+        let input = "Card 1: 41 48 83 | 83 86 6 31 17 9 48 53\nCard 2: 13 | 61\n";
+        let mut cards = CardReader::new(input.as_bytes()).unwrap();
+
+        let first = cards.next_card().unwrap().unwrap();
+        assert_eq!((first.card_id, first.matches()), (1, 2));
+        let second = cards.next_card().unwrap().unwrap();
+        assert_eq!(second.card_id, 2);
+        assert_eq!(second.winning_numbers, vec![13]);
+        assert_eq!(second.card_numbers, vec![61]);
+        assert_eq!(second.matches(), 0);
+        assert!(cards.next_card().is_none());
+    }
+}
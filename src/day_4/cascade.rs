@@ -0,0 +1,333 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::shared::Answer;
+
+/// what to do when a card wins copies of cards past the final card, which the puzzle promises never happens
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PastTheEnd {
+    /// win copies of the cards that exist and ignore the rest
+    #[default]
+    Clamp,
+    /// refuse to count the cards at all
+    Report,
+}
+
+impl FromStr for PastTheEnd {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "clamp" => Ok(PastTheEnd::Clamp),
+            "report" => Ok(PastTheEnd::Report),
+            other => Err(format!(
+                "Unknown handling `{}` for copies past the final card, expected clamp or report",
+                other
+            )),
+        }
+    }
+}
+
+/// the parts of a card the cascade needs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CardSummary {
+    pub card_id: usize,
+    /// the line the card was read from, counted from 1
+    pub line: usize,
+    pub matches: usize,
+}
+
+/// something about the card ids that would make the cascade count the wrong cards
+#[derive(Debug, Clone, PartialEq)]
+pub enum CardIdIssue {
+    /// card ids are counted from 1
+    ZeroId { line: usize },
+    Duplicate {
+        card_id: usize,
+        first_line: usize,
+        line: usize,
+    },
+    OutOfOrder {
+        card_id: usize,
+        line: usize,
+        previous_card_id: usize,
+    },
+    /// every id from `first` to `last` inclusive, so one huge id is a single issue
+    Missing { first: usize, last: usize },
+}
+
+impl fmt::Display for CardIdIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardIdIssue::ZeroId { line } => {
+                write!(f, "line {}: card 0, but cards count from 1", line)
+            }
+            CardIdIssue::Duplicate {
+                card_id,
+                first_line,
+                line,
+            } => write!(
+                f,
+                "line {}: card {} again, first seen on line {}",
+                line, card_id, first_line
+            ),
+            CardIdIssue::OutOfOrder {
+                card_id,
+                line,
+                previous_card_id,
+            } => write!(
+                f,
+                "line {}: card {} comes after card {}",
+                line, card_id, previous_card_id
+            ),
+            CardIdIssue::Missing { first, last } if first == last => {
+                write!(f, "card {} is missing", first)
+            }
+            CardIdIssue::Missing { first, last } => {
+                write!(f, "cards {} to {} are missing", first, last)
+            }
+        }
+    }
+}
+
+/// checks that the cards are numbered 1, 2, 3, ... in order, with none missing or repeated
+pub fn validate_ids(cards: &[CardSummary]) -> Vec<CardIdIssue> {
+    let mut issues = vec![];
+    let mut first_lines: HashMap<usize, usize> = HashMap::new();
+    let mut previous_card_id: Option<usize> = None;
+    for card in cards.iter() {
+        if card.card_id == 0 {
+            issues.push(CardIdIssue::ZeroId { line: card.line });
+            continue;
+        }
+        if let Some(&first_line) = first_lines.get(&card.card_id) {
+            issues.push(CardIdIssue::Duplicate {
+                card_id: card.card_id,
+                first_line,
+                line: card.line,
+            });
+            continue;
+        }
+        first_lines.insert(card.card_id, card.line);
+        if let Some(previous_card_id) = previous_card_id {
+            if previous_card_id > card.card_id {
+                issues.push(CardIdIssue::OutOfOrder {
+                    card_id: card.card_id,
+                    line: card.line,
+                    previous_card_id,
+                });
+            }
+        }
+        previous_card_id = Some(card.card_id);
+    }
+    let mut card_ids: Vec<usize> = first_lines.into_keys().collect();
+    card_ids.sort();
+    let mut previous_card_id = 0;
+    for card_id in card_ids {
+        if card_id > previous_card_id + 1 {
+            issues.push(CardIdIssue::Missing {
+                first: previous_card_id + 1,
+                last: card_id - 1,
+            });
+        }
+        previous_card_id = card_id;
+    }
+    issues
+}
+
+/// how many copies of one card we end up with, and where they came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardTrace {
+    pub card_id: usize,
+    pub matches: usize,
+    /// including the original
+    pub copies: Answer,
+    /// the cards that won copies of this one, with how many copies each won
+    pub won_from: Vec<(usize, Answer)>,
+}
+
+/// every card after the copies have cascaded down the table
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cascade {
+    pub cards: Vec<CardTrace>,
+    /// the copies that would have gone past the final card, as `(card id, how many cards past the end)`, when clamped
+    pub clamped: Vec<(usize, usize)>,
+}
+
+impl Cascade {
+    /// follows the copies each card wins down the table, after checking the card ids
+    pub fn run(cards: &[CardSummary], past_the_end: PastTheEnd) -> Result<Cascade, String> {
+        let issues = validate_ids(cards);
+        if !issues.is_empty() {
+            let issues: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+            return Err(format!("Invalid card ids:\n{}", issues.join("\n")));
+        }
+
+        // the ids are now 1, 2, 3, ... in order, so a card's id is one more than its index
+        let mut traces: Vec<CardTrace> = cards
+            .iter()
+            .map(|card| CardTrace {
+                card_id: card.card_id,
+                matches: card.matches,
+                copies: Answer::from(1),
+                won_from: vec![],
+            })
+            .collect();
+        let mut clamped = vec![];
+        for index in 0..traces.len() {
+            let (card_id, matches) = (traces[index].card_id, traces[index].matches);
+            let past = (card_id + matches).saturating_sub(traces.len());
+            if past > 0 {
+                match past_the_end {
+                    PastTheEnd::Clamp => clamped.push((card_id, past)),
+                    PastTheEnd::Report => {
+                        return Err(format!(
+                            "Card {} wins copies of {} card(s) past the final card {}",
+                            card_id,
+                            past,
+                            traces.len()
+                        ))
+                    }
+                }
+            }
+            let copies = traces[index].copies.clone();
+            for won in traces.iter_mut().skip(index + 1).take(matches) {
+                won.copies += copies.clone();
+                won.won_from.push((card_id, copies.clone()));
+            }
+        }
+        Ok(Cascade {
+            cards: traces,
+            clamped,
+        })
+    }
+
+    pub fn total(&self) -> Answer {
+        self.cards.iter().map(|card| card.copies.clone()).sum()
+    }
+}
+
+impl fmt::Display for Cascade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for card in self.cards.iter() {
+            write!(
+                f,
+                "card {}: {} matches, {} copies = 1 original",
+                card.card_id, card.matches, card.copies
+            )?;
+            for (card_id, copies) in card.won_from.iter() {
+                write!(f, " + {} from card {}", copies, card_id)?;
+            }
+            writeln!(f)?;
+        }
+        for (card_id, past) in self.clamped.iter() {
+            writeln!(
+                f,
+                "card {} would have won {} card(s) past the final card",
+                card_id, past
+            )?;
+        }
+        writeln!(f, "{} cards in total", self.total())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_ids, CardIdIssue, CardSummary, Cascade, PastTheEnd};
+
+    fn summaries(cards: &[(usize, usize)]) -> Vec<CardSummary> {
+        cards
+            .iter()
+            .enumerate()
+            .map(|(index, &(card_id, matches))| CardSummary {
+                card_id,
+                line: index + 1,
+                matches,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn traces_the_example() {
+        // the matches of the six cards in the puzzle's example
+        let cards = summaries(&[(1, 4), (2, 2), (3, 2), (4, 1), (5, 0), (6, 0)]);
+        let cascade = match Cascade::run(&cards, PastTheEnd::Report) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in Cascade::run: {err}"),
+        };
+
+        let copies: Vec<String> = cascade
+            .cards
+            .iter()
+            .map(|card| card.copies.to_string())
+            .collect();
+        assert_eq!(copies, vec!["1", "2", "4", "8", "14", "1"]);
+        assert_eq!(cascade.total(), 30);
+        assert!(cascade.to_string().contains(concat!(
+            "card 4: 1 matches, 8 copies = 1 original",
+            " + 1 from card 1 + 2 from card 2 + 4 from card 3\n"
+        )));
+    }
+
+    #[test]
+    fn finds_bad_card_ids() {
+        let cards = summaries(&[(1, 0), (3, 0), (2, 0), (3, 0), (0, 0), (6, 0)]);
+
+        assert_eq!(
+            validate_ids(&cards),
+            vec![
+                CardIdIssue::OutOfOrder {
+                    card_id: 2,
+                    line: 3,
+                    previous_card_id: 3
+                },
+                CardIdIssue::Duplicate {
+                    card_id: 3,
+                    first_line: 2,
+                    line: 4
+                },
+                CardIdIssue::ZeroId { line: 5 },
+                CardIdIssue::Missing { first: 4, last: 5 },
+            ]
+        );
+        assert!(Cascade::run(&cards, PastTheEnd::Clamp).is_err());
+    }
+
+    #[test]
+    fn reports_a_huge_gap_as_one_issue() {
+        let cards = summaries(&[(1, 0), (3, 0), (usize::MAX, 0)]);
+
+        assert_eq!(
+            validate_ids(&cards),
+            vec![
+                CardIdIssue::Missing { first: 2, last: 2 },
+                CardIdIssue::Missing {
+                    first: 4,
+                    last: usize::MAX - 1
+                },
+            ]
+        );
+        assert_eq!(
+            Cascade::run(&summaries(&[(usize::MAX, 0)]), PastTheEnd::Clamp),
+            Err(format!(
+                "Invalid card ids:\ncards 1 to {} are missing",
+                usize::MAX - 1
+            ))
+        );
+    }
+
+    #[test]
+    fn clamps_or_reports_copies_past_the_end() {
+        let cards = summaries(&[(1, 1), (2, 3), (3, 0)]);
+
+        let cascade = Cascade::run(&cards, PastTheEnd::Clamp).unwrap();
+        assert_eq!(cascade.clamped, vec![(2, 2)]);
+        assert_eq!(cascade.total(), 1 + 2 + 3);
+
+        assert_eq!(
+            Cascade::run(&cards, PastTheEnd::Report),
+            Err(String::from(
+                "Card 2 wins copies of 2 card(s) past the final card 3"
+            ))
+        );
+    }
+}
@@ -1,9 +1,14 @@
-use card_parser::{parse_card, CardInfo};
+use card_parser::{CardInfo, CardReader};
+use cascade::CardSummary;
 use std::fs;
 
-use crate::shared::DayResult;
+use crate::shared::{Answer, DayResult};
 
 pub mod card_parser;
+mod cascade;
+mod number_set;
+
+pub use cascade::{CardIdIssue, Cascade, PastTheEnd};
 
 pub fn solve() -> Result<DayResult, String> {
     let input = match fs::read_to_string("src/day_4/input.txt") {
@@ -18,101 +23,84 @@ pub fn solve() -> Result<DayResult, String> {
     return Ok(output);
 }
 
-fn solve_part_1(input: &str) -> Result<usize, String> {
-    let mut output: usize = 0;
-    for line in input.lines() {
-        let card = parse_card(line).map_err(|err| err.to_string())?;
-        output += calculate_part_1_card_score(&card);
-    }
-    return Ok(output);
+/// solves both parts, handling copies past the final card as `past_the_end` says
+pub fn solve_with(past_the_end: PastTheEnd) -> Result<DayResult, String> {
+    let input = read_input()?;
+    Ok(DayResult {
+        part_1: solve_part_1(&input)?,
+        part_2: solve_part_2_with(&input, past_the_end)?,
+    })
 }
 
-fn calculate_part_1_card_score(card: &CardInfo) -> usize {
-    let mut score: usize = 0;
-    for winning_number in card.winning_numbers.iter() {
-        let wins = card
-            .card_numbers
-            .iter()
-            .map(|number| winning_number == number)
-            .fold(0 as usize, |count, win| match win {
-                true => count + 1,
-                false => count,
-            });
-        for _ in 0..wins {
-            score = increment_score(score);
-        }
-    }
-
-    return score;
+/// how many copies of each card the puzzle input ends with, and which cards won them
+pub fn trace(past_the_end: PastTheEnd) -> Result<Cascade, String> {
+    Cascade::run(&read_summaries(&read_input()?)?, past_the_end)
 }
 
-fn increment_score(score: usize) -> usize {
-    match score {
-        0 => return 1,
-        _ => return score * 2,
-    }
+/// checks that the puzzle input's cards are numbered 1, 2, 3, ... in order
+pub fn validate() -> Result<Vec<CardIdIssue>, String> {
+    Ok(cascade::validate_ids(&read_summaries(&read_input()?)?))
 }
 
-struct CardCounts {
-    card_counts: Vec<usize>
+fn read_input() -> Result<String, String> {
+    match fs::read_to_string("src/day_4/input.txt") {
+        Ok(result) => Ok(result),
+        Err(err) => Err(format!("Error in reading file: {}", err)),
+    }
 }
 
-impl CardCounts {
-    fn new() -> CardCounts{
-        return CardCounts { card_counts: Vec::new() }
+/// reads the id, line and number of matches of every card
+fn read_summaries(input: &str) -> Result<Vec<CardSummary>, String> {
+    let mut cards = CardReader::new(input.as_bytes()).map_err(|err| err.to_string())?;
+    let mut summaries = vec![];
+    while let Some(card) = cards.next_card() {
+        let card = card.map_err(|err| err.to_string())?;
+        let (card_id, matches) = (card.card_id, card.matches());
+        summaries.push(CardSummary {
+            card_id,
+            line: cards.line_number(),
+            matches,
+        });
     }
+    Ok(summaries)
+}
 
-    fn get_count(&self, index: usize) -> &usize {
-        match self.card_counts.get(index) {
-            Some(result) => return result,
-            None => return &1,
-        }
+fn solve_part_1(input: &str) -> Result<Answer, String> {
+    let mut cards = CardReader::new(input.as_bytes()).map_err(|err| err.to_string())?;
+    let mut output = Answer::from(0);
+    while let Some(card) = cards.next_card() {
+        let card = card.map_err(|err| err.to_string())?;
+        output += calculate_part_1_card_score(card);
     }
+    return Ok(output);
+}
 
-    fn add_count(&mut self, index: usize, count: usize) {
-        while self.card_counts.len() <= index {
-            self.card_counts.push(1 as usize) // we always have the original card
-        };
-        self.card_counts[index] += count;
+fn calculate_part_1_card_score(card: &CardInfo) -> Answer {
+    let mut score = Answer::from(0);
+    for _ in 0..card.matches() {
+        score = increment_score(score);
     }
 
-    fn get_sum_of_all_counts(&self) -> usize {
-        let result: usize = self.card_counts.iter().sum();
-        return result
-    }
+    score
 }
 
-fn solve_part_2(input: &str) -> Result<usize, String> {
-    let mut counts = CardCounts::new();
-    
-    for line in input.lines() {
-        let card = parse_card(line).map_err(|err| err.to_string())?;
-        let wins = get_card_wins(&card);
-        for i in 0..wins {
-            counts.add_count(card.card_id + i, *counts.get_count(card.card_id - 1))
-        }
+fn increment_score(score: Answer) -> Answer {
+    match score {
+        Answer::Small(0) => Answer::from(1),
+        _ => score * Answer::from(2),
     }
-
-    return Ok(counts.get_sum_of_all_counts())
 }
 
-fn get_card_wins(card: &CardInfo) -> usize {
-    let mut total_wins:usize = 0;
-    for winning_number in card.winning_numbers.iter() {
-        let wins = card
-            .card_numbers
-            .iter()
-            .map(|number| winning_number == number)
-            .fold(0 as usize, |count, win| match win {
-                true => count + 1,
-                false => count,
-            });
-        total_wins += wins;
-        }
-    return total_wins
+fn solve_part_2(input: &str) -> Result<Answer, String> {
+    solve_part_2_with(input, PastTheEnd::default())
 }
 
+fn solve_part_2_with(input: &str, past_the_end: PastTheEnd) -> Result<Answer, String> {
+    let cascade = Cascade::run(&read_summaries(input)?, past_the_end)?;
+    Ok(cascade.total())
+}
 
+#[cfg(test)]
 mod tests {
     use super::*;
     mod test_examples {
@@ -139,13 +127,77 @@ mod tests {
 
         #[test]
         fn test_example_1_card_1() {
-            use super::parse_card;
             use super::calculate_part_1_card_score;
-
-            let card = parse_card("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53").expect("error parsing card");
+            use super::CardInfo;
+            use crate::day_4::card_parser::CardParser;
+
+            let mut card = CardInfo::default();
+            CardParser::new()
+                .and_then(|parser| {
+                    parser.parse_into(
+                        "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+                        1,
+                        &mut card,
+                    )
+                })
+                .expect("error parsing card");
             let result = calculate_part_1_card_score(&card);
 
             assert_eq!(result, 8)
         }
+
+        #[test]
+        fn test_score_past_a_usize() {
+            use super::calculate_part_1_card_score;
+            use super::CardInfo;
+            use crate::day_4::card_parser::CardParser;
+
+            let numbers: Vec<String> = (1..=70).map(|number| number.to_string()).collect();
+            let line = format!("Card 3: {} | {}", numbers.join(" "), numbers.join(" "));
+            let mut card = CardInfo::default();
+            CardParser::new()
+                .and_then(|parser| parser.parse_into(&line, 1, &mut card))
+                .expect("error parsing card");
+            let result = calculate_part_1_card_score(&card);
+
+            assert_eq!(result.to_string(), "590295810358705651712")
+        }
     }
-}
\ No newline at end of file
+
+    mod test_card_counts {
+        #[test]
+        fn test_counts_past_a_usize() {
+            use super::solve_part_2;
+
+            // every card but the last two wins a copy of each of the next two,
+            // so the counts grow like the Fibonacci numbers
+            let input: String = (1..=100)
+                .map(|id: usize| {
+                    let matches: Vec<String> = (1..=2.min(100 - id))
+                        .map(|number| number.to_string())
+                        .collect();
+                    format!("Card {id}: 1 2 | {} 99\n", matches.join(" "))
+                })
+                .collect();
+
+            let result = match solve_part_2(&input) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in solve_part_2: {err}"),
+            };
+
+            assert_eq!(result.to_string(), "2427893228399975082350")
+        }
+
+        #[test]
+        fn test_huge_card_id_is_reported_quickly() {
+            use super::solve_part_2;
+
+            assert_eq!(
+                solve_part_2("Card 99999999999: 1 | 1\n"),
+                Err(String::from(
+                    "Invalid card ids:\ncards 1 to 99999999998 are missing"
+                ))
+            );
+        }
+    }
+}
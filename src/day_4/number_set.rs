@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+
+/// a set of the numbers on a card. numbers below 128, which is all the puzzle uses, are bits of `small`,
+/// and anything larger goes in `large`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NumberSet {
+    small: u128,
+    large: HashSet<usize>,
+}
+
+impl NumberSet {
+    /// adds `number`, returning whether it was new to the set
+    pub fn insert(&mut self, number: usize) -> bool {
+        match number < 128 {
+            true => {
+                let new = !self.contains(number);
+                self.small |= 1 << number;
+                new
+            }
+            false => self.large.insert(number),
+        }
+    }
+
+    pub fn contains(&self, number: usize) -> bool {
+        match number < 128 {
+            true => self.small & (1 << number) != 0,
+            false => self.large.contains(&number),
+        }
+    }
+
+    /// empties the set, keeping the space `large` already has
+    pub fn clear(&mut self) {
+        self.small = 0;
+        self.large.clear();
+    }
+}
+
+impl FromIterator<usize> for NumberSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(numbers: I) -> Self {
+        let mut set = NumberSet::default();
+        for number in numbers {
+            set.insert(number);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NumberSet;
+
+    #[test]
+    fn holds_small_and_large_numbers() {
+        let set: NumberSet = [0, 6, 127, 128, 1_000_000].into_iter().collect();
+
+        for number in [0, 6, 127, 128, 1_000_000] {
+            assert!(set.contains(number), "{number} should be in the set");
+        }
+        for number in [1, 5, 7, 126, 129, 999_999] {
+            assert!(!set.contains(number), "{number} should not be in the set");
+        }
+    }
+
+    #[test]
+    fn insert_says_whether_a_number_is_new() {
+        let mut set = NumberSet::default();
+
+        assert!(set.insert(5));
+        assert!(!set.insert(5));
+        assert!(set.insert(500));
+        assert!(!set.insert(500));
+        set.clear();
+        assert!(!set.contains(5) && !set.contains(500));
+    }
+}
@@ -0,0 +1,225 @@
+use crate::day_5::almanac_map::{AlmanacMap, AlmanacMapError};
+use std::{collections::HashMap, fmt};
+use thiserror::Error;
+
+/// the name of one of the almanac's categories, such as `seed`, `soil` or `location`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Category(String);
+
+impl Category {
+    pub fn new(name: &str) -> Self {
+        Self(name.to_string())
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// the almanac as a graph of categories, where each `x-to-y` map is an edge from `x` to `y`.
+/// maps may be added in any order; chains are only resolved when a pair of categories is queried
+pub struct Almanac {
+    maps: HashMap<Category, (Category, AlmanacMap)>,
+}
+
+/// one `x-to-y` map along a chain of categories
+pub struct AlmanacLink<'a> {
+    pub source: &'a Category,
+    pub destination: &'a Category,
+    pub map: &'a AlmanacMap,
+}
+
+#[derive(Debug, Error)]
+pub enum AlmanacError {
+    #[error("More than one map from category `{0}`")]
+    DuplicateMap(Category),
+
+    #[error("Unknown category `{0}`")]
+    UnknownCategory(Category),
+
+    #[error("No map from category `{stuck_at}`, so `{from}` cannot reach `{to}`")]
+    MissingLink {
+        from: Category,
+        to: Category,
+        stuck_at: Category,
+    },
+
+    #[error("Cyclic category link: {}", format_cycle(.0))]
+    CyclicLink(Vec<Category>),
+
+    #[error("Error composing maps: {0}")]
+    Compose(#[from] AlmanacMapError),
+}
+
+fn format_cycle(cycle: &[Category]) -> String {
+    cycle
+        .iter()
+        .map(|category| category.to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+impl Almanac {
+    pub fn new() -> Self {
+        Self {
+            maps: HashMap::new(),
+        }
+    }
+
+    /// adds the map from `source` to `destination`; each category can only be mapped from once
+    pub fn insert(
+        &mut self,
+        source: Category,
+        destination: Category,
+        map: AlmanacMap,
+    ) -> Result<(), AlmanacError> {
+        if self.maps.contains_key(&source) {
+            return Err(AlmanacError::DuplicateMap(source));
+        }
+        self.maps.insert(source, (destination, map));
+        Ok(())
+    }
+
+    fn knows(&self, category: &Category) -> bool {
+        self.maps.contains_key(category)
+            || self
+                .maps
+                .values()
+                .any(|(destination, _)| destination == category)
+    }
+
+    /// follows the maps from `from` until reaching `to`, returning them in the order they apply
+    pub fn chain(
+        &self,
+        from: &Category,
+        to: &Category,
+    ) -> Result<Vec<AlmanacLink<'_>>, AlmanacError> {
+        for category in [from, to] {
+            if !self.knows(category) {
+                return Err(AlmanacError::UnknownCategory(category.clone()));
+            }
+        }
+
+        let mut visited: Vec<&Category> = vec![from];
+        let mut output: Vec<AlmanacLink> = Vec::new();
+        let mut current = from;
+        while current != to {
+            let (source, (destination, map)) =
+                self.maps
+                    .get_key_value(current)
+                    .ok_or_else(|| AlmanacError::MissingLink {
+                        from: from.clone(),
+                        to: to.clone(),
+                        stuck_at: current.clone(),
+                    })?;
+            if let Some(index) = visited.iter().position(|category| *category == destination) {
+                let mut cycle: Vec<Category> = visited[index..]
+                    .iter()
+                    .map(|&category| category.clone())
+                    .collect();
+                cycle.push(destination.clone());
+                return Err(AlmanacError::CyclicLink(cycle));
+            }
+            visited.push(destination);
+            output.push(AlmanacLink {
+                source,
+                destination,
+                map,
+            });
+            current = destination;
+        }
+        Ok(output)
+    }
+
+    /// builds the single map that takes values in `from` to values in `to`
+    pub fn map_between(&self, from: &Category, to: &Category) -> Result<AlmanacMap, AlmanacError> {
+        let chain = self.chain(from, to)?;
+        Ok(AlmanacMap::compose_chain(
+            chain.iter().map(|link| link.map),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Almanac, AlmanacError, Category};
+    use crate::day_5::{almanac_map::AlmanacMap, map_tuple::MapTuple};
+
+    fn build_almanac(links: &[(&str, &str)]) -> Almanac {
+        let mut almanac = Almanac::new();
+        for (index, (source, destination)) in links.iter().enumerate() {
+            let map = match AlmanacMap::from_vec(vec![MapTuple::new(index * 10 + 10, 0, 10)]) {
+                Ok(result) => result,
+                Err(err) => panic!("Error building map: {err}"),
+            };
+            if let Err(err) = almanac.insert(Category::new(source), Category::new(destination), map)
+            {
+                panic!("Error inserting map: {err}");
+            }
+        }
+        almanac
+    }
+
+    #[test]
+    fn resolves_maps_given_out_of_order() {
+        let almanac = build_almanac(&[("soil", "water"), ("seed", "soil"), ("water", "light")]);
+
+        let seed_to_water =
+            match almanac.map_between(&Category::new("seed"), &Category::new("water")) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in map_between: {err}"),
+            };
+
+        // seed 5 -> soil 25 (unmapped by soil-to-water)
+        assert_eq!(seed_to_water.get_mapped_value(5), 25);
+        // seed 15 is unmapped all the way through
+        assert_eq!(seed_to_water.get_mapped_value(15), 15);
+        assert_eq!(
+            match almanac.chain(&Category::new("soil"), &Category::new("light")) {
+                Ok(result) => result.len(),
+                Err(err) => panic!("Error in chain: {err}"),
+            },
+            2
+        );
+    }
+
+    #[test]
+    fn reports_missing_links() {
+        let almanac = build_almanac(&[("seed", "soil"), ("water", "light")]);
+
+        let result = almanac.chain(&Category::new("seed"), &Category::new("light"));
+
+        match result {
+            Err(AlmanacError::MissingLink { stuck_at, .. }) => {
+                assert_eq!(stuck_at, Category::new("soil"))
+            }
+            _ => panic!("expected a missing link"),
+        }
+        assert!(matches!(
+            almanac.chain(&Category::new("seed"), &Category::new("humidity")),
+            Err(AlmanacError::UnknownCategory(_))
+        ));
+    }
+
+    #[test]
+    fn reports_cyclic_links() {
+        let almanac = build_almanac(&[
+            ("seed", "soil"),
+            ("soil", "water"),
+            ("water", "soil"),
+            ("light", "location"),
+        ]);
+
+        let result = almanac.chain(&Category::new("seed"), &Category::new("location"));
+
+        match result {
+            Err(err @ AlmanacError::CyclicLink(_)) => assert_eq!(
+                err.to_string(),
+                "Cyclic category link: soil -> water -> soil"
+            ),
+            _ => panic!("expected a cyclic link"),
+        }
+    }
+}
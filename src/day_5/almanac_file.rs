@@ -0,0 +1,529 @@
+use crate::day_5::map_tuple::MapTuple;
+use crate::shared::{parse_numbers, Location, NumberError};
+use std::fmt;
+use thiserror::Error;
+
+/// the almanac exactly as written, keeping the line number of every seed list, map and row
+/// so that problems can be reported against the original text
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlmanacFile {
+    pub seeds_line: usize,
+    pub seeds: Vec<usize>,
+    pub maps: Vec<MapBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapBlock {
+    pub line: usize,
+    pub source: String,
+    pub destination: String,
+    pub rows: Vec<MapRow>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapRow {
+    pub line: usize,
+    pub map_tuple: MapTuple,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AlmanacFileError {
+    /// not a number, or too large for a `usize`, located by line
+    #[error(transparent)]
+    InvalidNumber(#[from] NumberError),
+
+    #[error("line {0}: expected a `destination source length` row")]
+    InvalidRowFormat(usize),
+
+    #[error("line {0}: expected a `source-to-destination map:` header")]
+    InvalidHeader(usize),
+
+    #[error("line {0}: mapping row found before any map header")]
+    RowOutsideMap(usize),
+
+    #[error("line {0}: seeds listed more than once")]
+    DuplicateSeeds(usize),
+
+    #[error("No `seeds:` line found")]
+    MissingSeeds,
+}
+
+/// something in an almanac that the solvers would reject or silently mishandle
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlmanacIssue {
+    ZeroLengthMapTuple {
+        map: String,
+        line: usize,
+    },
+    /// a MapTuple whose source or destination range runs past `usize::MAX`
+    OverflowingMapTuple {
+        map: String,
+        line: usize,
+    },
+    OverlappingMapTuples {
+        map: String,
+        first_line: usize,
+        second_line: usize,
+    },
+    /// the last seed has no length to pair with
+    OddSeedCount {
+        line: usize,
+        seeds: usize,
+    },
+    /// seed sets are numbered from 1, in the order they are listed
+    ZeroLengthSeedSet {
+        line: usize,
+        seed_set: usize,
+    },
+    OverflowingSeedSet {
+        line: usize,
+        seed_set: usize,
+    },
+    OverlappingSeedSets {
+        line: usize,
+        first_seed_set: usize,
+        second_seed_set: usize,
+    },
+}
+
+impl fmt::Display for AlmanacIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacIssue::ZeroLengthMapTuple { map, line } => {
+                write!(f, "line {line}: zero-length MapTuple in {map} map")
+            }
+            AlmanacIssue::OverflowingMapTuple { map, line } => {
+                write!(
+                    f,
+                    "line {line}: MapTuple in {map} map runs past {}",
+                    usize::MAX
+                )
+            }
+            AlmanacIssue::OverlappingMapTuples {
+                map,
+                first_line,
+                second_line,
+            } => write!(
+                f,
+                "lines {first_line} and {second_line}: overlapping MapTuples in {map} map"
+            ),
+            AlmanacIssue::OddSeedCount { line, seeds } => {
+                write!(
+                    f,
+                    "line {line}: {seeds} seeds cannot be paired into seed sets"
+                )
+            }
+            AlmanacIssue::ZeroLengthSeedSet { line, seed_set } => {
+                write!(f, "line {line}: seed set {seed_set} has zero length")
+            }
+            AlmanacIssue::OverflowingSeedSet { line, seed_set } => {
+                write!(
+                    f,
+                    "line {line}: seed set {seed_set} runs past {}",
+                    usize::MAX
+                )
+            }
+            AlmanacIssue::OverlappingSeedSets {
+                line,
+                first_seed_set,
+                second_seed_set,
+            } => write!(
+                f,
+                "line {line}: seed sets {first_seed_set} and {second_seed_set} overlap"
+            ),
+        }
+    }
+}
+
+impl MapBlock {
+    pub fn name(&self) -> String {
+        format!("{}-to-{}", self.source, self.destination)
+    }
+}
+
+/// helper function to find overlaps among `(label, start, length)` ranges, ignoring empty ones.
+/// each range is checked against whichever earlier range reaches furthest, so every overlapping
+/// range is reported at least once
+fn find_overlaps(mut ranges: Vec<(usize, usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.retain(|(_, _, length)| *length > 0);
+    ranges.sort_by_key(|(label, start, _)| (*start, *label));
+
+    let mut output = Vec::new();
+    let mut furthest: Option<(usize, usize)> = None; // (label, end)
+    for (label, start, length) in ranges {
+        let end = start.saturating_add(length);
+        if let Some((furthest_label, furthest_end)) = furthest {
+            if start < furthest_end {
+                output.push((furthest_label.min(label), furthest_label.max(label)));
+            }
+            if end <= furthest_end {
+                continue;
+            }
+        }
+        furthest = Some((label, end));
+    }
+    output
+}
+
+impl AlmanacFile {
+    pub fn parse(input: &str) -> Result<Self, AlmanacFileError> {
+        let mut seeds: Option<(usize, Vec<usize>)> = None;
+        let mut maps: Vec<MapBlock> = Vec::new();
+
+        for (index, raw_line) in input.lines().enumerate() {
+            let line = index + 1;
+            let text = raw_line.trim();
+            let parse_numbers = |numbers: &str| parse_numbers(numbers, Location::Line(line));
+
+            if text.is_empty() {
+                continue;
+            } else if let Some(numbers) = text.strip_prefix("seeds:") {
+                if seeds.is_some() {
+                    return Err(AlmanacFileError::DuplicateSeeds(line));
+                }
+                seeds = Some((line, parse_numbers(numbers)?));
+            } else if let Some(header) = text.strip_suffix(" map:") {
+                let (source, destination) = header
+                    .split_once("-to-")
+                    .ok_or(AlmanacFileError::InvalidHeader(line))?;
+                maps.push(MapBlock {
+                    line,
+                    source: source.to_string(),
+                    destination: destination.to_string(),
+                    rows: Vec::new(),
+                });
+            } else {
+                let numbers = parse_numbers(text)?;
+                if numbers.len() != 3 {
+                    return Err(AlmanacFileError::InvalidRowFormat(line));
+                }
+                let block = maps
+                    .last_mut()
+                    .ok_or(AlmanacFileError::RowOutsideMap(line))?;
+                block.rows.push(MapRow {
+                    line,
+                    map_tuple: MapTuple::new(numbers[0], numbers[1], numbers[2]),
+                });
+            }
+        }
+
+        let (seeds_line, seeds) = seeds.ok_or(AlmanacFileError::MissingSeeds)?;
+        Ok(Self {
+            seeds_line,
+            seeds,
+            maps,
+        })
+    }
+
+    /// finds every zero-length, overflowing or overlapping MapTuple and seed set, and a seed left without a length
+    pub fn validate(&self) -> Vec<AlmanacIssue> {
+        let mut issues = Vec::new();
+
+        if !self.seeds.len().is_multiple_of(2) {
+            issues.push(AlmanacIssue::OddSeedCount {
+                line: self.seeds_line,
+                seeds: self.seeds.len(),
+            });
+        }
+
+        let seed_sets: Vec<(usize, usize, usize)> = self
+            .seeds
+            .chunks_exact(2)
+            .enumerate()
+            .map(|(index, chunk)| (index + 1, chunk[0], chunk[1]))
+            .collect();
+        for (seed_set, _, _) in seed_sets.iter().filter(|(_, _, length)| *length == 0) {
+            issues.push(AlmanacIssue::ZeroLengthSeedSet {
+                line: self.seeds_line,
+                seed_set: *seed_set,
+            });
+        }
+        for (seed_set, _, _) in seed_sets
+            .iter()
+            .filter(|(_, start, length)| start.checked_add(*length).is_none())
+        {
+            issues.push(AlmanacIssue::OverflowingSeedSet {
+                line: self.seeds_line,
+                seed_set: *seed_set,
+            });
+        }
+        for (first_seed_set, second_seed_set) in find_overlaps(seed_sets) {
+            issues.push(AlmanacIssue::OverlappingSeedSets {
+                line: self.seeds_line,
+                first_seed_set,
+                second_seed_set,
+            });
+        }
+
+        for block in self.maps.iter() {
+            for row in block
+                .rows
+                .iter()
+                .filter(|row| row.map_tuple.range_length == 0)
+            {
+                issues.push(AlmanacIssue::ZeroLengthMapTuple {
+                    map: block.name(),
+                    line: row.line,
+                });
+            }
+            for row in block.rows.iter().filter(|row| {
+                let map_tuple = &row.map_tuple;
+                map_tuple
+                    .source_range_start
+                    .checked_add(map_tuple.range_length)
+                    .is_none()
+                    || map_tuple
+                        .destination_range_start
+                        .checked_add(map_tuple.range_length)
+                        .is_none()
+            }) {
+                issues.push(AlmanacIssue::OverflowingMapTuple {
+                    map: block.name(),
+                    line: row.line,
+                });
+            }
+            let ranges = block
+                .rows
+                .iter()
+                .map(|row| {
+                    (
+                        row.line,
+                        row.map_tuple.source_range_start,
+                        row.map_tuple.range_length,
+                    )
+                })
+                .collect();
+            for (first_line, second_line) in find_overlaps(ranges) {
+                issues.push(AlmanacIssue::OverlappingMapTuples {
+                    map: block.name(),
+                    first_line,
+                    second_line,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// rewrites every map in its simplest form: rows sorted by source, zero-length and identity rows
+    /// dropped, and neighbouring rows that shift by the same offset merged into one.
+    /// only meaningful for an almanac without overlapping MapTuples. rows that overflow are kept, never merged.
+    /// line numbers are updated to match the text that `Display` would write
+    pub fn normalise(&self) -> Self {
+        let mut output = self.clone();
+        for block in output.maps.iter_mut() {
+            let mut map_tuples: Vec<MapTuple> = block
+                .rows
+                .iter()
+                .map(|row| row.map_tuple.clone())
+                .filter(|map_tuple| {
+                    map_tuple.range_length > 0
+                        && map_tuple.destination_range_start != map_tuple.source_range_start
+                })
+                .collect();
+            map_tuples.sort_by_key(|map_tuple| map_tuple.source_range_start);
+
+            let mut merged: Vec<MapTuple> = Vec::new();
+            for map_tuple in map_tuples {
+                let merged_length = merged.last().and_then(|previous| {
+                    let source_end = previous
+                        .source_range_start
+                        .checked_add(previous.range_length)?;
+                    let destination_end = previous
+                        .destination_range_start
+                        .checked_add(previous.range_length)?;
+                    let adjacent = source_end == map_tuple.source_range_start
+                        && destination_end == map_tuple.destination_range_start;
+                    match adjacent {
+                        true => previous.range_length.checked_add(map_tuple.range_length),
+                        false => None,
+                    }
+                });
+                match (merged.last_mut(), merged_length) {
+                    (Some(previous), Some(range_length)) => previous.range_length = range_length,
+                    _ => merged.push(map_tuple),
+                }
+            }
+
+            block.rows = merged
+                .into_iter()
+                .map(|map_tuple| MapRow { line: 0, map_tuple })
+                .collect();
+        }
+        output.renumber();
+        output
+    }
+
+    /// sets every line number to where it would fall in the text that `Display` writes
+    fn renumber(&mut self) {
+        self.seeds_line = 1;
+        let mut line = 1;
+        for block in self.maps.iter_mut() {
+            line += 2; // blank separator, then the header
+            block.line = line;
+            for row in block.rows.iter_mut() {
+                line += 1;
+                row.line = line;
+            }
+        }
+    }
+}
+
+/// writes the almanac back out in the puzzle's own format
+impl fmt::Display for AlmanacFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seeds = self
+            .seeds
+            .iter()
+            .map(|seed| seed.to_string())
+            .collect::<Vec<_>>();
+        writeln!(f, "seeds: {}", seeds.join(" "))?;
+        for block in self.maps.iter() {
+            writeln!(f)?;
+            writeln!(f, "{} map:", block.name())?;
+            for row in block.rows.iter() {
+                writeln!(
+                    f,
+                    "{} {} {}",
+                    row.map_tuple.destination_range_start,
+                    row.map_tuple.source_range_start,
+                    row.map_tuple.range_length
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AlmanacFile, AlmanacFileError, AlmanacIssue};
+    use crate::shared::{Location, NumberError};
+
+    fn parse(input: &str) -> AlmanacFile {
+        match AlmanacFile::parse(input) {
+            Ok(result) => result,
+            Err(err) => panic!("Error parsing almanac: {err}"),
+        }
+    }
+
+    #[test]
+    fn writes_the_example_back_unchanged() {
+        use std::fs;
+
+        let example_input = match fs::read_to_string("src/day_5/test_input_part_1.txt") {
+            Ok(result) => result,
+            Err(err) => panic!("Error in file reading: {err}"),
+        };
+
+        let almanac_file = parse(&example_input);
+
+        assert!(almanac_file.validate().is_empty());
+        assert_eq!(
+            almanac_file.to_string().trim_end(),
+            example_input.trim_end()
+        );
+    }
+
+    #[test]
+    fn reports_issues_with_line_numbers() {
+        let almanac_file =
+            parse("seeds: 10 5 12 4 30 0\n\nseed-to-soil map:\n50 98 2\n52 50 48\n0 60 3\n9 9 0\n");
+
+        assert_eq!(
+            almanac_file.validate(),
+            vec![
+                AlmanacIssue::ZeroLengthSeedSet {
+                    line: 1,
+                    seed_set: 3
+                },
+                AlmanacIssue::OverlappingSeedSets {
+                    line: 1,
+                    first_seed_set: 1,
+                    second_seed_set: 2
+                },
+                AlmanacIssue::ZeroLengthMapTuple {
+                    map: String::from("seed-to-soil"),
+                    line: 7
+                },
+                AlmanacIssue::OverlappingMapTuples {
+                    map: String::from("seed-to-soil"),
+                    first_line: 5,
+                    second_line: 6
+                },
+            ]
+        );
+        assert_eq!(
+            almanac_file.validate()[3].to_string(),
+            "lines 5 and 6: overlapping MapTuples in seed-to-soil map"
+        );
+    }
+
+    #[test]
+    fn reports_overflowing_rows_and_an_unpaired_seed() {
+        let max = usize::MAX;
+        let almanac_file = parse(&format!(
+            "seeds: {max} 2 7\n\nseed-to-soil map:\n0 {max} 1\n{max} 0 2\n5 5 5\n"
+        ));
+
+        assert_eq!(
+            almanac_file.validate(),
+            vec![
+                AlmanacIssue::OddSeedCount { line: 1, seeds: 3 },
+                AlmanacIssue::OverflowingSeedSet {
+                    line: 1,
+                    seed_set: 1
+                },
+                AlmanacIssue::OverflowingMapTuple {
+                    map: String::from("seed-to-soil"),
+                    line: 4
+                },
+                AlmanacIssue::OverflowingMapTuple {
+                    map: String::from("seed-to-soil"),
+                    line: 5
+                },
+            ]
+        );
+        assert_eq!(
+            almanac_file.validate()[0].to_string(),
+            "line 1: 3 seeds cannot be paired into seed sets"
+        );
+        // normalising keeps the overflowing rows rather than panicking on them
+        assert_eq!(almanac_file.normalise().maps[0].rows.len(), 2);
+    }
+
+    #[test]
+    fn normalises_by_merging_rows_with_the_same_offset() {
+        let almanac_file = parse(
+            "seeds: 1 2\n\nseed-to-soil map:\n70 20 10\n50 0 10\n60 10 10\n5 5 5\n1 2 0\n\nsoil-to-water map:\n0 1 1\n",
+        );
+
+        let normalised = almanac_file.normalise();
+
+        assert_eq!(
+            normalised.to_string(),
+            "seeds: 1 2\n\nseed-to-soil map:\n50 0 30\n\nsoil-to-water map:\n0 1 1\n"
+        );
+        assert_eq!(normalised.maps[1].line, 6);
+        assert_eq!(normalised.maps[1].rows[0].line, 7);
+    }
+
+    #[test]
+    fn reports_parse_errors_with_line_numbers() {
+        assert_eq!(
+            AlmanacFile::parse("seeds: 1 2\n\n1 2 3\n"),
+            Err(AlmanacFileError::RowOutsideMap(3))
+        );
+        assert_eq!(
+            AlmanacFile::parse("seeds: 1 2\n\nseed-to-soil map:\n1 2\n"),
+            Err(AlmanacFileError::InvalidRowFormat(4))
+        );
+        assert_eq!(
+            AlmanacFile::parse("seeds: 1 2\n\nseed-to-soil map:\n1 2 99999999999999999999\n"),
+            Err(AlmanacFileError::InvalidNumber(NumberError::TooLarge {
+                text: String::from("99999999999999999999"),
+                location: Location::Line(4)
+            }))
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use crate::day_5::map_tuple::MapTuple;
+use crate::shared::IntervalMap;
+use std::{fmt, ops::Range};
+use thiserror::Error;
+
+/// a single `x-to-y` map from the almanac, with its `MapTuple`s indexed by source range
+pub struct AlmanacMap {
+    map_tuples: IntervalMap<usize, MapTuple>,
+}
+
+#[derive(Debug, Error)]
+pub enum AlmanacMapError {
+    #[error("Attempted to use overlapping MapTuples")]
+    OverlappingMapTuples,
+
+    #[error("MapTuple range exceeds usize max")]
+    Overflow,
+}
+
+impl AlmanacMap {
+    pub fn new() -> Self {
+        Self {
+            map_tuples: IntervalMap::new(),
+        }
+    }
+
+    /// constructs a map from a Vec of MapTuples in any order
+    pub fn from_vec(input_vec: Vec<MapTuple>) -> Result<Self, AlmanacMapError> {
+        let mut new_map = AlmanacMap::new();
+        for map_tuple in input_vec {
+            if map_tuple.range_length == 0 {
+                continue; // maps nothing, so there is nothing to store
+            }
+            if map_tuple
+                .source_range_start
+                .checked_add(map_tuple.range_length)
+                .is_none()
+                || map_tuple
+                    .destination_range_start
+                    .checked_add(map_tuple.range_length)
+                    .is_none()
+            {
+                return Err(AlmanacMapError::Overflow);
+            }
+            new_map
+                .map_tuples
+                .try_insert(map_tuple.source_range(), map_tuple)
+                .map_err(|_| AlmanacMapError::OverlappingMapTuples)?;
+        }
+        Ok(new_map)
+    }
+
+    /// finds the MapTuple that contains the source_input value and calculates the destination output;
+    /// if there is no MapTuple that contains the source_input value, it is unmapped, and we return the same value
+    pub fn get_mapped_value(&self, source_input: usize) -> usize {
+        match self.map_tuples.get(source_input) {
+            Some((_, node)) => node
+                .calculate_output(source_input)
+                .expect("interval lookup returned a MapTuple that does not contain the value"),
+            None => source_input,
+        }
+    }
+
+    /// maps every value in `points` in place, given `points` sorted by value, in a single merge pass over the MapTuples.
+    /// returns the positions where a new piece of the output starts: every value within a piece is shifted by the
+    /// same amount, so each piece is still sorted afterwards.
+    /// the second element of each pair is left alone, so callers can use it to remember where a point came from
+    pub fn map_sorted_points(&self, points: &mut [(usize, usize)]) -> Vec<usize> {
+        let (Some(&(first, _)), Some(&(last, _))) = (points.first(), points.last()) else {
+            return Vec::new();
+        };
+        // no MapTuple can contain usize::MAX, as its end would overflow
+        let mut map_tuples = self
+            .map_tuples
+            .overlapping(first..last.saturating_add(1))
+            .peekable();
+
+        let mut boundaries = Vec::new();
+        let mut map_tuples_passed = 0;
+        let mut previous_piece = None;
+        for (position, (value, _)) in points.iter_mut().enumerate() {
+            while map_tuples
+                .next_if(|(range, _)| range.end <= *value)
+                .is_some()
+            {
+                map_tuples_passed += 1;
+            }
+            let mapped = match map_tuples.peek() {
+                Some((range, map_tuple)) if range.start <= *value => {
+                    *value = map_tuple.destination_range_start + (*value - range.start);
+                    true
+                }
+                _ => false,
+            };
+            let piece = (map_tuples_passed, mapped);
+            if previous_piece.is_some_and(|previous| previous != piece) {
+                boundaries.push(position);
+            }
+            previous_piece = Some(piece);
+        }
+        boundaries
+    }
+
+    /// iterates, in order, over the MapTuples whose source range intersects `range`
+    pub fn overlapping(&self, range: Range<usize>) -> impl Iterator<Item = &MapTuple> + '_ {
+        self.map_tuples.overlapping(range).map(|(_, node)| node)
+    }
+
+    /// splits `range` into consecutive pieces, each either covered by a single MapTuple or unmapped
+    pub fn segments(&self, range: Range<usize>) -> Vec<(Range<usize>, Option<&MapTuple>)> {
+        let mut output = Vec::new();
+        let mut current_value = range.start;
+        for map_tuple in self.overlapping(range.clone()) {
+            let map_tuple_range = map_tuple.source_range();
+            if current_value < map_tuple_range.start {
+                output.push((current_value..map_tuple_range.start, None));
+                current_value = map_tuple_range.start;
+            }
+            let critical_point = map_tuple_range.end.min(range.end);
+            output.push((current_value..critical_point, Some(map_tuple)));
+            current_value = critical_point;
+        }
+        if current_value < range.end {
+            output.push((current_value..range.end, None));
+        }
+        output
+    }
+
+    /// returns every piece of the source space that maps into `range`, as MapTuples sorted by source.
+    /// values that are unmapped land on themselves, so they come back as identity MapTuples
+    pub fn preimage(&self, range: Range<usize>) -> Vec<MapTuple> {
+        let mut pieces: Vec<MapTuple> = self
+            .segments(range.clone())
+            .into_iter()
+            .filter(|(_, map_tuple)| map_tuple.is_none())
+            .map(|(segment, _)| MapTuple::new(segment.start, segment.start, segment.len()))
+            .collect();
+        // destinations are not indexed, and may overlap each other, so every MapTuple is checked
+        pieces.extend(
+            self.map_tuples
+                .iter()
+                .filter_map(|(_, map_tuple)| map_tuple.preimage(range.clone())),
+        );
+        pieces.sort_by_key(|map_tuple| map_tuple.source_range_start);
+        pieces
+    }
+
+    /// returns every source value that maps onto `value`, in ascending order
+    pub fn preimage_of_value(&self, value: usize) -> Result<Vec<usize>, AlmanacMapError> {
+        let end = value.checked_add(1).ok_or(AlmanacMapError::Overflow)?;
+        Ok(self
+            .preimage(value..end)
+            .into_iter()
+            .map(|map_tuple| map_tuple.source_range_start)
+            .collect())
+    }
+
+    /// iterates over the MapTuples in order of source range
+    pub fn iter(&self) -> impl Iterator<Item = &MapTuple> + '_ {
+        self.map_tuples.iter().map(|(_, map_tuple)| map_tuple)
+    }
+
+    /// builds the single map equivalent to applying `self` and then `next`
+    pub fn compose(&self, next: &AlmanacMap) -> Result<AlmanacMap, AlmanacMapError> {
+        let mut composed: Vec<MapTuple> = Vec::new();
+        for (range, map_tuple) in self.segments(0..usize::MAX) {
+            match map_tuple {
+                Some(map_tuple) => composed.extend(map_tuple.compose(next)),
+                // unmapped by `self`, so it behaves as an identity MapTuple over the gap
+                None => composed
+                    .extend(MapTuple::new(range.start, range.start, range.len()).compose(next)),
+            }
+        }
+        AlmanacMap::from_vec(composed)
+    }
+
+    /// folds a chain of maps, applied in order, into a single map
+    pub fn compose_chain<'a>(
+        maps: impl IntoIterator<Item = &'a AlmanacMap>,
+    ) -> Result<AlmanacMap, AlmanacMapError> {
+        maps.into_iter()
+            .try_fold(AlmanacMap::new(), |composed, map| composed.compose(map))
+    }
+}
+
+/// writes the MapTuples as `destination source length` rows, in the same format as the almanac
+impl fmt::Display for AlmanacMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for map_tuple in self.iter() {
+            writeln!(
+                f,
+                "{} {} {}",
+                map_tuple.destination_range_start,
+                map_tuple.source_range_start,
+                map_tuple.range_length
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AlmanacMap, AlmanacMapError};
+    use crate::day_5::map_tuple::MapTuple;
+
+    #[test]
+    fn maps_values_from_sorted_tuples() {
+        // sorted input used to build a linked list, and overflowed the tree keys past 63 tuples
+        let map_tuples = (0..1_000)
+            .map(|i| MapTuple::new(i * 10 + 5, i * 10, 5))
+            .collect::<Vec<_>>();
+        let map = match AlmanacMap::from_vec(map_tuples) {
+            Ok(result) => result,
+            Err(e) => panic!("Error building map: {e}"),
+        };
+
+        assert_eq!(map.get_mapped_value(9_992), 9_997);
+        assert_eq!(map.get_mapped_value(9_996), 9_996);
+    }
+
+    #[test]
+    fn rejects_overlapping_tuples() {
+        let result = AlmanacMap::from_vec(vec![MapTuple::new(0, 10, 5), MapTuple::new(0, 12, 5)]);
+
+        assert!(matches!(result, Err(AlmanacMapError::OverlappingMapTuples)));
+    }
+
+    #[test]
+    fn maps_sorted_points_and_reports_where_pieces_start() {
+        let map =
+            match AlmanacMap::from_vec(vec![MapTuple::new(50, 98, 2), MapTuple::new(52, 50, 48)]) {
+                Ok(result) => result,
+                Err(e) => panic!("Error building map: {e}"),
+            };
+        let mut points = vec![
+            (10, 0),
+            (20, 1),
+            (50, 2),
+            (97, 3),
+            (98, 4),
+            (99, 5),
+            (120, 6),
+        ];
+
+        let boundaries = map.map_sorted_points(&mut points);
+
+        let values = points.iter().map(|(value, _)| *value).collect::<Vec<_>>();
+        assert_eq!(values, vec![10, 20, 52, 99, 50, 51, 120]);
+        assert_eq!(boundaries, vec![2, 4, 6]);
+    }
+}
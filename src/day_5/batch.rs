@@ -0,0 +1,133 @@
+use crate::day_5::{
+    almanac::{Almanac, AlmanacError, Category},
+    almanac_map::AlmanacMap,
+};
+use std::ops::Range;
+
+/// maps every value in `points` from category `from` to category `to`, returning the results in the same order.
+/// suited to millions of points: see `map_points_through`
+pub fn map_points(
+    almanac: &Almanac,
+    from: &Category,
+    to: &Category,
+    points: &[usize],
+) -> Result<Vec<usize>, AlmanacError> {
+    let chain = almanac.chain(from, to)?;
+    Ok(map_points_through(
+        chain.iter().map(|link| link.map),
+        points,
+    ))
+}
+
+/// maps every value in `points` through each of `maps` in turn, returning the results in the same order.
+///
+/// the points are sorted once up front, then each map is applied with a single merge pass over its sorted MapTuples.
+/// a map shifts each of its source ranges as a block, so sorted points come out as a few sorted runs;
+/// each later map sweeps those runs one at a time rather than sorting the points again
+pub fn map_points_through<'a>(
+    maps: impl IntoIterator<Item = &'a AlmanacMap>,
+    points: &[usize],
+) -> Vec<usize> {
+    let mut tagged_points: Vec<(usize, usize)> = points
+        .iter()
+        .enumerate()
+        .map(|(index, &value)| (value, index))
+        .collect();
+    tagged_points.sort_unstable();
+
+    // every point starts out in one sorted run
+    let mut runs: Vec<Range<usize>> = std::iter::once(0..tagged_points.len()).collect();
+    for map in maps {
+        let mut next_runs: Vec<Range<usize>> = Vec::with_capacity(runs.len());
+        for run in runs {
+            let mut start = run.start;
+            for boundary in map.map_sorted_points(&mut tagged_points[run.clone()]) {
+                push_run(&mut next_runs, &tagged_points, start..run.start + boundary);
+                start = run.start + boundary;
+            }
+            push_run(&mut next_runs, &tagged_points, start..run.end);
+        }
+        runs = next_runs;
+    }
+
+    let mut output = vec![0; points.len()];
+    for (value, index) in tagged_points {
+        output[index] = value;
+    }
+    output
+}
+
+/// helper function to add a sorted run, joining it onto the previous run when they are still in order together
+fn push_run(runs: &mut Vec<Range<usize>>, points: &[(usize, usize)], run: Range<usize>) {
+    if let Some(previous) = runs.last_mut() {
+        if previous.end == run.start
+            && (previous.start == previous.end
+                || run.is_empty()
+                || points[run.start - 1].0 <= points[run.start].0)
+        {
+            previous.end = run.end;
+            return;
+        }
+    }
+    runs.push(run);
+}
+
+/// the straightforward alternative to `map_points_through`: looks every point up in every map on its own.
+/// kept as a reference for tests and benchmarks
+pub fn map_points_one_by_one<'a>(
+    maps: impl IntoIterator<Item = &'a AlmanacMap> + Clone,
+    points: &[usize],
+) -> Vec<usize> {
+    points
+        .iter()
+        .map(|&point| {
+            maps.clone()
+                .into_iter()
+                .fold(point, |value, map| map.get_mapped_value(value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{map_points_one_by_one, map_points_through};
+    use crate::day_5::{almanac_map::AlmanacMap, map_tuple::MapTuple};
+
+    fn build_map(map_tuples: Vec<MapTuple>) -> AlmanacMap {
+        match AlmanacMap::from_vec(map_tuples) {
+            Ok(result) => result,
+            Err(err) => panic!("Error building map: {err}"),
+        }
+    }
+
+    #[test]
+    fn matches_looking_up_points_one_by_one() {
+        let maps = [
+            build_map(vec![MapTuple::new(50, 98, 2), MapTuple::new(52, 50, 48)]),
+            build_map(vec![
+                MapTuple::new(0, 15, 37),
+                MapTuple::new(37, 52, 2),
+                MapTuple::new(39, 0, 15),
+            ]),
+            build_map(vec![]),
+        ];
+        // unsorted, with duplicates and points on every boundary
+        let points: Vec<usize> = vec![99, 0, 14, 15, 49, 50, 51, 97, 98, 100, 79, 14, 55, 13, 0];
+
+        assert_eq!(
+            map_points_through(maps.iter(), &points),
+            map_points_one_by_one(maps.iter(), &points)
+        );
+    }
+
+    #[test]
+    fn keeps_points_in_their_original_order() {
+        let maps = [build_map(vec![MapTuple::new(0, 10, 10)])];
+
+        assert_eq!(
+            map_points_through(maps.iter(), &[15, 5, 10, 25]),
+            vec![5, 5, 0, 25]
+        );
+        assert_eq!(map_points_through(maps.iter(), &[]), Vec::<usize>::new());
+    }
+}
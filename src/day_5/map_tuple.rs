@@ -1,8 +1,8 @@
-use crate::day_5::SearchDirection;
-use std::cmp::Ordering;
+use crate::day_5::almanac_map::AlmanacMap;
+use std::ops::Range;
 use thiserror::Error;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MapTuple {
     pub destination_range_start: usize,
     pub source_range_start: usize,
@@ -13,9 +13,6 @@ pub struct MapTuple {
 pub enum MapTupleError {
     #[error("Attempted to calculate the output for a value that is not in range")]
     DoesNotContain,
-
-    #[error("Source range overlaps")]
-    Overlap,
 }
 
 impl MapTuple {
@@ -43,187 +40,51 @@ impl MapTuple {
         }
     }
 
-    pub fn does_not_overlap(&self, other: &Self) -> bool {
-        return self.source_range_start + self.range_length <= other.source_range_start
-            || other.source_range_start + other.range_length <= self.source_range_start;
+    /// returns the half-open source range `[start, start + length)`
+    pub fn source_range(&self) -> Range<usize> {
+        self.source_range_start..self.source_range_start + self.range_length
     }
 
-    pub fn compare_without_overlap(&self, other: &Self) -> Result<Ordering, MapTupleError> {
-        if !self.does_not_overlap(other) {
-            return Err(MapTupleError::Overlap);
-        }
-        match self.source_range_start > other.source_range_start {
-            true => return Ok(Ordering::Greater),
-            false => return Ok(Ordering::Less),
-        }
+    /// returns the half-open destination range `[start, start + length)`
+    pub fn destination_range(&self) -> Range<usize> {
+        self.destination_range_start..self.destination_range_start + self.range_length
     }
 
-    pub fn get_search_direction(&self, value: usize) -> SearchDirection {
-        match (self.contains(value), value < self.source_range_start) {
-            (true, _) => return SearchDirection::Contains,
-            (false, true) => return SearchDirection::Less,
-            (false, false) => return SearchDirection::Greater,
+    /// restricts `self` to the part whose destination lies in `range`, if there is any
+    pub fn preimage(&self, range: Range<usize>) -> Option<Self> {
+        let destination_range = self.destination_range();
+        let start = destination_range.start.max(range.start);
+        let end = destination_range.end.min(range.end);
+        if start >= end {
+            return None;
         }
+        Some(Self::new(
+            start,
+            self.source_range_start + (start - self.destination_range_start),
+            end - start,
+        ))
     }
 
-    /* /// calculates and returns the
-        pub fn compose(&self, other: &Self) -> Vec<Self> {
-            if self.does_not_overlap(other) {
-                // if they do not overlap, they do not interact
-                return vec![self.clone(), other.clone()];
-            }
-
-            struct TupleRange {
-                start: usize,
-                stop: usize,
-                steps: usize,
-            }
-
-            impl TupleRange {
-                fn new(start: usize, stop: usize) -> Option<Self> {
-                    if stop - start <= 0 {
-                        return None;
+    /// calculates the MapTuples for applying `self` and then `next`, covering the whole source range of `self`.
+    /// pieces that end up mapping back onto themselves are identity, and are left out
+    pub fn compose(&self, next: &AlmanacMap) -> Vec<Self> {
+        next.segments(self.destination_range())
+            .into_iter()
+            .filter_map(|(range, next_tuple)| {
+                let source_start =
+                    self.source_range_start + (range.start - self.destination_range_start);
+                let destination_start = match next_tuple {
+                    Some(map_tuple) => {
+                        map_tuple.destination_range_start
+                            + (range.start - map_tuple.source_range_start)
                     }
-                    return Some(TupleRange {
-                        start: start,
-                        stop: stop,
-                        steps: stop - start,
-                    });
+                    None => range.start,
+                };
+                match source_start == destination_start {
+                    true => None,
+                    false => Some(Self::new(destination_start, source_start, range.len())),
                 }
-
-                fn intersection_range(&self, other: &Self) -> Option<Self> {
-                    if self.start >= other.stop || self.stop <= other.start {
-                        return None; // no intersection
-                    }
-                    match (self.start <= other.start, self.stop < other.stop) {
-                        (true, true) => {
-                            // self, self + other, other
-                            return TupleRange::new(other.start, self.stop);
-                        }
-                        (false, true) => {
-                            // other, self + other, other
-                            return TupleRange::new(self.start, self.stop);
-                        }
-                        (true, false) => {
-                            // self, other + self, self
-                            return TupleRange::new(other.start, other.stop);
-                        }
-                        (false, false) => {
-                            // other, other + self, self
-                            return TupleRange::new(self.start, other.stop);
-                        }
-                    }
-                }
-
-                fn inclusive_intersection_range(&self, other: &Self) -> Vec<TupleRange> {
-                    let mut output: Vec<TupleRange> = Vec::new();
-                    if self.start >= other.stop || self.stop <= other.start {
-                        // no intersection
-                        if let Some(range) = TupleRange::new(self.start, self.stop) {
-                            output.push(range);
-                        }
-                        if let Some(range) = TupleRange::new(other.start, other.stop) {
-                            output.push(range);
-                        }
-                        return output;
-                    }
-                    match (self.start <= other.start, self.stop < other.stop) {
-                        (true, true) => {
-                            // self, self + other, other
-
-                            if let Some(range) = TupleRange::new(self.start, other.start) {
-                                output.push(range);
-                            }
-                            if let Some(range) = TupleRange::new(other.start, self.stop) {
-                                output.push(range);
-                            }
-                            if let Some(range) = TupleRange::new(self.stop, other.stop) {
-                                output.push(range);
-                            }
-                            return output;
-                        }
-                        (false, true) => {
-                            // other, self + other, other
-                            if let Some(range) = TupleRange::new(other.start, self.start) {
-                                output.push(range);
-                            }
-                            if let Some(range) = TupleRange::new(self.start, self.stop) {
-                                output.push(range);
-                            }
-                            if let Some(range) = TupleRange::new(self.stop, other.stop) {
-                                output.push(range);
-                            }
-                            return output;
-                        }
-                        (true, false) => {
-                            // self, other + self, self
-                            if let Some(range) = TupleRange::new(self.start, other.start) {
-                                output.push(range);
-                            }
-                            if let Some(range) = TupleRange::new(other.start, other.stop) {
-                                output.push(range);
-                            }
-                            if let Some(range) = TupleRange::new(other.stop, self.stop) {
-                                output.push(range);
-                            }
-                            return output;
-                        }
-                        (false, false) => {
-                            // other, other + self, self
-                            if let Some(range) = TupleRange::new(other.start, self.start) {
-                                output.push(range);
-                            }
-                            if let Some(range) = TupleRange::new(self.start, other.stop) {
-                                output.push(range);
-                            }
-                            if let Some(range) = TupleRange::new(other.stop, self.stop) {
-                                output.push(range);
-                            }
-                            return output;
-                        }
-                    }
-                }
-
-                fn range_map(&self, diff: isize) -> Result<Self, String> {
-                    if self.start as isize + diff < 0 {
-                        return Err("Cannot create negative values in range".to_string());
-                    } else {
-                        if let Some(range) = TupleRange::new(
-                            (self.start as isize + diff) as usize,
-                            (self.stop as isize + diff) as usize,
-                        ) {
-                            return Ok(range);
-                        } else {
-                            return Err("Invalid range".to_string());
-                        }
-                    }
-                }
-            }
-
-            let self_input_range = TupleRange::new(
-                self.source_range_start,
-                self.source_range_start + self.range_length,
-            )
-            .unwrap();
-            let self_output_range = TupleRange::new(
-                self.destination_range_start,
-                self.destination_range_start + self.range_length,
-            )
-            .unwrap();
-
-            let other_input_range = TupleRange::new(
-                other.source_range_start,
-                other.source_range_start + other.range_length,
-            )
-            .unwrap();
-            let other_output_range = TupleRange::new(
-                other.destination_range_start,
-                other.destination_range_start + other.range_length,
-            )
-            .unwrap();
-
-            let a = self_input_range;
-            let ab = self_output_range.intersection_range(&other_input_range).range_map(other.source_range_start);
-            let b = other_input_range
-        } */
+            })
+            .collect()
+    }
 }
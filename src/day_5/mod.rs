@@ -1,22 +1,30 @@
 use crate::{
     day_5::{
-        linear_range_binary_search_tree::LinearRangeBinarySearchTree,
-        map_binary_search_tree::{MapBinarySearchTree, MapBinarySearchTreeError},
+        almanac::{Almanac, AlmanacError, Category},
+        almanac_file::{AlmanacFile, AlmanacIssue},
+        almanac_map::{AlmanacMap, AlmanacMapError},
         seed_set::SeedSet,
     },
-    shared::DayResult,
+    shared::{
+        parse_number, parse_numbers, DayResult, IntervalMap, IntervalMapError, Location,
+        NumberError,
+    },
 };
-use linear_range::{LinearRange, LinearRangeError};
-use linear_range_binary_search_tree::LinearRangeBinarySearchTreeError;
+use batch::{map_points, map_points_one_by_one, map_points_through};
+use linear_range::{LinearRange, LinearRangeError, TrackedRange};
 use map_tuple::{MapTuple, MapTupleError};
 use regex::Regex;
-use std::{fs, num::ParseIntError};
+use std::{fmt, fs, ops::Range, time::Instant};
 use thiserror::Error;
+use trace::{trace, SeedTrace};
 
+mod almanac;
+mod almanac_file;
+mod almanac_map;
+mod batch;
 mod linear_range;
-mod linear_range_binary_search_tree;
-mod map_binary_search_tree;
 mod map_tuple;
+mod trace;
 
 pub fn solve() -> Result<DayResult, String> {
     let input = match fs::read_to_string("src/day_5/input.txt") {
@@ -24,30 +32,205 @@ pub fn solve() -> Result<DayResult, String> {
         Err(err) => return Err(format!("Error in reading file: {}", err)),
     };
     let output = DayResult {
-        part_1: solve_part_1(&input)?,
-        part_2: solve_part_2(&input)?,
+        part_1: solve_part_1(&input)?.into(),
+        part_2: solve_part_2(&input)?.into(),
     };
 
     return Ok(output);
 }
 
+/// traces a seed (`79`) or half-open seed range (`79..93`) from the puzzle input through every map to its location
+pub fn explain(seeds: &str) -> Result<SeedTrace, String> {
+    let input = match fs::read_to_string("src/day_5/input.txt") {
+        Ok(result) => result,
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
+    };
+    let values = parse_value_range(seeds)?;
+    let parsed_input = parse_input_part_1(&input).map_err(|e| e.to_string())?;
+    trace(
+        &parsed_input.almanac,
+        &Category::new("seed"),
+        &Category::new("location"),
+        values,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// the almanac at `path` with all of its maps folded into one seed-to-location map,
+/// written as `seed-to-location map:` rows in the almanac's own format
+pub fn print_seed_to_location_map(path: &str) -> Result<String, String> {
+    let input = match fs::read_to_string(path) {
+        Ok(result) => result,
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
+    };
+    let parsed_input = parse_input_part_2(&input).map_err(|e| e.to_string())?;
+    let location_map =
+        get_seed_to_location_map(&parsed_input.almanac).map_err(|e| e.to_string())?;
+    Ok(format!("seed-to-location map:\n{}", location_map))
+}
+
+/// checks the almanac at `path` for zero-length or overlapping MapTuples and seed sets
+pub fn validate_almanac(path: &str) -> Result<Vec<AlmanacIssue>, String> {
+    Ok(read_almanac_file(path)?.validate())
+}
+
+/// writes the almanac at `path` to `output_path` with every map in its simplest form,
+/// refusing if any of its maps has overlapping or overflowing MapTuples
+pub fn normalise_almanac(path: &str, output_path: &str) -> Result<(), String> {
+    let almanac_file = read_almanac_file(path)?;
+    let overlaps: Vec<String> = almanac_file
+        .validate()
+        .iter()
+        .filter(|issue| {
+            matches!(
+                issue,
+                AlmanacIssue::OverlappingMapTuples { .. }
+                    | AlmanacIssue::OverflowingMapTuple { .. }
+            )
+        })
+        .map(|issue| issue.to_string())
+        .collect();
+    if !overlaps.is_empty() {
+        return Err(format!(
+            "Cannot normalise an almanac with overlapping or overflowing MapTuples:\n{}",
+            overlaps.join("\n")
+        ));
+    }
+    fs::write(output_path, almanac_file.normalise().to_string())
+        .map_err(|err| format!("Error in writing file: {}", err))
+}
+
+fn read_almanac_file(path: &str) -> Result<AlmanacFile, String> {
+    let input = match fs::read_to_string(path) {
+        Ok(result) => result,
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
+    };
+    AlmanacFile::parse(&input).map_err(|e| e.to_string())
+}
+
+/// times mapping `count` pseudo-random seeds to locations through the puzzle input's maps,
+/// once with the batched sweep and once looking each seed up on its own
+pub fn benchmark_map_points(count: usize) -> Result<String, String> {
+    let input = match fs::read_to_string("src/day_5/input.txt") {
+        Ok(result) => result,
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
+    };
+    let parsed_input = parse_input_part_1(&input).map_err(|e| e.to_string())?;
+    let chain = parsed_input
+        .almanac
+        .chain(&Category::new("seed"), &Category::new("location"))
+        .map_err(|e| e.to_string())?;
+    let maps = chain.iter().map(|link| link.map);
+
+    // xorshift, so runs are repeatable without pulling in a random number crate
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let points: Vec<usize> = (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 32) as usize
+        })
+        .collect();
+
+    let start = Instant::now();
+    let batched = map_points_through(maps.clone(), &points);
+    let batched_time = start.elapsed();
+    let start = Instant::now();
+    let one_by_one = map_points_one_by_one(maps, &points);
+    let one_by_one_time = start.elapsed();
+
+    if batched != one_by_one {
+        return Err("Batched and one by one locations disagree".to_string());
+    }
+    Ok(format!(
+        "Mapped {} seeds: batched sweep {:?}, one by one {:?}",
+        count, batched_time, one_by_one_time
+    ))
+}
+
+/// helper function to read either a single value or a half-open `start..end` range
+fn parse_value_range(input: &str) -> Result<Range<usize>, String> {
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<usize>()
+            .map_err(|e| format!("Invalid value `{value}`: {e}"))
+    };
+    match input.split_once("..") {
+        Some((start, end)) => Ok(parse(start)?..parse(end)?),
+        None => {
+            let value = parse(input)?;
+            let end = value
+                .checked_add(1)
+                .ok_or(format!("Value `{value}` is too large to trace"))?;
+            Ok(value..end)
+        }
+    }
+}
+
 fn solve_part_1(input: &str) -> Result<usize, String> {
+    Ok(find_part_1_witness(input)?.location)
+}
+
+fn solve_part_2(input: &str) -> Result<usize, String> {
+    Ok(find_part_2_witness(input)?.location)
+}
+
+/// the seed behind an answer: which seed reaches the lowest location, and for part 2 which seed set it came from
+#[derive(Debug, PartialEq)]
+pub struct Witness {
+    pub location: usize,
+    pub seed: usize,
+    pub seed_set: Option<SeedSet>,
+}
+
+impl fmt::Display for Witness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.seed_set {
+            Some(seed_set) => write!(
+                f,
+                "seed {} (from seed set {} {}) reaches location {}",
+                self.seed, seed_set.start, seed_set.steps, self.location
+            ),
+            None => write!(f, "seed {} reaches location {}", self.seed, self.location),
+        }
+    }
+}
+
+/// finds the witnesses for both parts of the puzzle input
+pub fn find_witnesses() -> Result<(Witness, Witness), String> {
+    let input = match fs::read_to_string("src/day_5/input.txt") {
+        Ok(result) => result,
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
+    };
+    Ok((find_part_1_witness(&input)?, find_part_2_witness(&input)?))
+}
+
+fn find_part_1_witness(input: &str) -> Result<Witness, String> {
     let parsed_input = parse_input_part_1(input).map_err(|e| e.to_string())?;
     println!(
         "Calculating locations for {0} seeds",
         parsed_input.seeds.len()
     );
-    let minimum_location = parsed_input
+    let locations = map_points(
+        &parsed_input.almanac,
+        &Category::new("seed"),
+        &Category::new("location"),
+        &parsed_input.seeds,
+    )
+    .map_err(|e| e.to_string())?;
+    let witness = parsed_input
         .seeds
         .into_iter()
-        .map(|seed| {
-            parsed_input
-                .maps
-                .iter()
-                .fold(seed, |current, tree| tree.get_mapped_value(current))
+        .zip(locations)
+        .map(|(seed, location)| Witness {
+            location,
+            seed,
+            seed_set: None,
         })
-        .min();
-    match minimum_location {
+        .min_by_key(|witness| witness.location);
+    match witness {
         Some(value) => {
             println!("Part 1 done!");
             Ok(value)
@@ -56,236 +239,187 @@ fn solve_part_1(input: &str) -> Result<usize, String> {
     }
 }
 
-fn solve_part_2(input: &str) -> Result<usize, String> {
-    let parsed_input = match parse_input_part_2(input) {
-        Ok(mut result) => {
-            result
-                .seed_sets
-                .sort_by(|a, b| a.compare_without_overlap(b).unwrap());
-            result
-        }
-        Err(e) => return Err(e.to_string()),
+fn find_part_2_witness(input: &str) -> Result<Witness, String> {
+    let parsed_input = parse_input_part_2(input).map_err(|e| e.to_string())?;
+    let location_map =
+        get_seed_to_location_map(&parsed_input.almanac).map_err(|e| e.to_string())?;
+
+    let seed_ranges = parsed_input
+        .seed_sets
+        .iter()
+        .map(TrackedRange::from_seed_set)
+        .collect::<Result<Vec<TrackedRange>, LinearRangeError>>()
+        .map_err(|e| e.to_string())?;
+
+    let location_ranges = merge_linear_ranges_and_map_tuples(&seed_ranges, &location_map)
+        .map_err(|e| e.to_string())?;
+
+    let lowest = location_ranges
+        .first()
+        .ok_or(String::from("No LinearMaps survived"))?;
+
+    Ok(Witness {
+        location: lowest.range.start,
+        seed: lowest.origin,
+        seed_set: Some(lowest.seed_set),
+    })
+}
+
+/// solves part 2 a second way, by searching the locations upward, and checks it agrees with `solve_part_2`.
+/// also lists the seeds that land on the lowest location
+pub fn check_part_2() -> Result<String, String> {
+    let input = match fs::read_to_string("src/day_5/input.txt") {
+        Ok(result) => result,
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
     };
+    let expected = solve_part_2(&input)?;
 
-    let map_iter = parsed_input.maps.into_iter();
-    let mut current_input_ranges: Vec<LinearRange> = parsed_input
+    let parsed_input = parse_input_part_2(&input).map_err(|e| e.to_string())?;
+    let location_map =
+        get_seed_to_location_map(&parsed_input.almanac).map_err(|e| e.to_string())?;
+    let seed_ranges = parsed_input
         .seed_sets
         .iter()
-        .map(|seed_set| LinearRange::from_seed_set(seed_set))
+        .map(LinearRange::from_seed_set)
         .collect::<Result<Vec<LinearRange>, LinearRangeError>>()
         .map_err(|e| e.to_string())?;
-
-    for map in map_iter {
-        let next_input_ranges =
-            merge_linear_ranges_and_map_tuples(&current_input_ranges, &map.get_sorted_vec())
-                .map_err(|e| e.to_string())?;
-        current_input_ranges = next_input_ranges;
+    let seed_ranges = merge_seed_ranges(&seed_ranges).map_err(|e| e.to_string())?;
+    let searched = lowest_location_searching_upward(&location_map, &seed_ranges)
+        .ok_or("Searching upward found no location that a seed reaches")?;
+    if searched != expected {
+        return Err(format!(
+            "Part 2 disagrees: solve_part_2 gave {}, searching upward gave {}",
+            expected, searched
+        ));
     }
 
-    let output = current_input_ranges
-        .first()
-        .ok_or(String::from("No LinearMaps survived"))?
-        .start;
+    let seeds: Vec<String> = location_map
+        .preimage_of_value(searched)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|seed| {
+            seed_ranges
+                .iter()
+                .any(|seed_range| seed_range.contains(seed))
+        })
+        .map(|seed| seed.to_string())
+        .collect();
+    Ok(format!(
+        "Searching upward agrees with part 2: {}\nSeeds landing there: {}",
+        searched,
+        seeds.join(", ")
+    ))
+}
 
-    Ok(output)
+/// folds the almanac's maps from `seed` through to `location` into a single map
+fn get_seed_to_location_map(almanac: &Almanac) -> Result<AlmanacMap, AlmanacError> {
+    almanac.map_between(&Category::new("seed"), &Category::new("location"))
 }
 
-/// Takes a sorted `LinearRange` vec and a sorted `MapTuple` vec and iterates over both,
-/// pairing where appropriate to create a new vec of (sorted) `LinearRange`s.
-/// Designed to ensure all values in the original `LinearRange`s are either carried over or mapped into the output
-fn merge_linear_ranges_and_map_tuples(
-    input_ranges: &[LinearRange],
-    map_tuples: &[MapTuple],
-) -> Result<Vec<LinearRange>, MergeError> {
-    // initialise out-of-loop values
-    let mut input_range_iter = input_ranges.iter().peekable();
-    let mut map_tuple_iter = map_tuples.iter().peekable();
-    let mut current_input_value = input_ranges.first().map(|range| range.start).unwrap_or(0);
-    let mut output_tree = LinearRangeBinarySearchTree::new();
-
-    loop {
-        // copy the values
-        let next_input_range = input_range_iter.peek().copied();
-        let next_map_tuple = map_tuple_iter.peek().copied();
-        // then work with the copied values to avoid locking the iterators
-        match (next_input_range, next_map_tuple) {
-            (Some(input_range), Some(map_tuple)) => {
-                if map_tuple.source_range_start + map_tuple.range_length >= input_range.start {
-                    match get_critical_point(current_input_value, input_range, map_tuple) {
-                        CriticalPointEnum::StartOfInputRange(critical_point) => {
-                            current_input_value = critical_point; // move to start of next input range
-                        }
-                        CriticalPointEnum::StartOfMapTuple(critical_point) => {
-                            // from start of input range to start of map tuple, maps to itself
-                            let steps = critical_point
-                                .checked_sub(current_input_value)
-                                .ok_or(MergeError::Underflow)?;
-                            let range = LinearRange::new(current_input_value, steps)
-                                .map_err(|e| MergeError::FailedToCreateLinearRange(e))?;
-                            output_tree.unbalanced_insert(range)?;
-                            current_input_value = critical_point;
-                        }
-                        CriticalPointEnum::EndOfMapTuple(critical_point) => {
-                            // from the current_input point to the end of the MapTuple
-                            let start = map_tuple.calculate_output(current_input_value)?;
-                            let steps = critical_point
-                                .checked_sub(current_input_value)
-                                .ok_or(MergeError::Underflow)?;
-
-                            let range = LinearRange::new(start, steps)
-                                .map_err(|e| MergeError::FailedToCreateLinearRange(e))?;
-                            output_tree.unbalanced_insert(range)?;
-                            current_input_value = critical_point;
-                            map_tuple_iter.next();
-                        }
-                        CriticalPointEnum::EndOfInputRange(critical_point) => {
-                            // from the current_input point to the end of the input range
-                            // but we have to check if we are in a MapTuple or not
-                            if map_tuple.contains(current_input_value) {
-                                // we are in a MapTuple
-                                let start = map_tuple.calculate_output(current_input_value)?;
-                                let steps = critical_point
-                                    .checked_sub(current_input_value)
-                                    .ok_or(MergeError::Underflow)?;
-
-                                let range = LinearRange::new(start, steps)
-                                    .map_err(|e| MergeError::FailedToCreateLinearRange(e))?;
-                                output_tree.unbalanced_insert(range)?;
-                            } else {
-                                // we are not in a MapTuple
-                                let steps = critical_point
-                                    .checked_sub(current_input_value)
-                                    .ok_or(MergeError::Underflow)?;
-                                let range = LinearRange::new(current_input_value, steps)
-                                    .map_err(|e| MergeError::FailedToCreateLinearRange(e))?;
-                                output_tree.unbalanced_insert(range)?;
-                            }
-                            current_input_value = critical_point;
-                            input_range_iter.next();
-                        }
-                        CriticalPointEnum::StartOfNextInputRange => {
-                            // we have to consume the current value to get to the next one
-                            let _ = input_range_iter.next();
-                        }
+/// merges overlapping seed ranges so seeds listed by more than one seed set are only searched once
+fn merge_seed_ranges(seed_ranges: &[LinearRange]) -> Result<Vec<Range<usize>>, IntervalMapError> {
+    let mut merged: IntervalMap<usize, ()> = IntervalMap::new();
+    for seed_range in seed_ranges {
+        merged.insert_merging(seed_range.as_range(), (), |_, _| ())?;
+    }
+    Ok(merged.iter().map(|(range, _)| range).collect())
+}
+
+/// Alternative to part 2 that works backwards from the location space: location ranges are visited
+/// upwards, and the first one whose preimage reaches any seed range holds the lowest location.
+/// Mostly useful as an independent check on `solve_part_2`
+fn lowest_location_searching_upward(
+    location_map: &AlmanacMap,
+    seed_ranges: &[Range<usize>],
+) -> Option<usize> {
+    // between consecutive breakpoints every location has the same set of preimage MapTuples
+    let mut breakpoints: Vec<usize> = location_map
+        .iter()
+        .flat_map(|map_tuple| {
+            [
+                map_tuple.source_range().start,
+                map_tuple.source_range().end,
+                map_tuple.destination_range().start,
+                map_tuple.destination_range().end,
+            ]
+        })
+        .collect();
+    breakpoints.extend([0, usize::MAX]);
+    breakpoints.sort_unstable();
+    breakpoints.dedup();
+
+    for window in breakpoints.windows(2) {
+        let lowest_location = location_map
+            .preimage(window[0]..window[1])
+            .iter()
+            .flat_map(|map_tuple| {
+                let source_range = map_tuple.source_range();
+                seed_ranges.iter().filter_map(move |seed_range| {
+                    let lowest_seed = seed_range.start.max(source_range.start);
+                    match lowest_seed < seed_range.end.min(source_range.end) {
+                        true => map_tuple.calculate_output(lowest_seed).ok(),
+                        false => None,
                     }
-                } else {
-                    let _ = map_tuple_iter.next();
-                }
-            }
-            (Some(&input), None) => {
-                if current_input_value > input.start {
-                    // we are part way through the current LinearRange
-                    let steps = input
-                        .steps
-                        .checked_sub(
-                            current_input_value
-                                .checked_sub(input.start)
-                                .ok_or(MergeError::Underflow)?,
-                        )
-                        .ok_or(MergeError::Underflow)?;
-                    let rest_of_input = LinearRange::new(current_input_value, steps)?;
-                    output_tree
-                        .unbalanced_insert(rest_of_input)
-                        .map_err(|e| MergeError::BinaryTreeOverflow(e))?;
-                } else {
-                    output_tree
-                        .unbalanced_insert(input)
-                        .map_err(|e| MergeError::BinaryTreeOverflow(e))?;
-                }
+                })
+            })
+            .min();
+        if lowest_location.is_some() {
+            return lowest_location;
+        }
+    }
+    None
+}
 
-                input_range_iter.next();
-            }
-            (None, _) => break,
+/// Takes a slice of `TrackedRange`s and an `AlmanacMap` and walks each range through the
+/// `MapTuple`s it intersects, creating a new vec of `TrackedRange`s sorted by start.
+/// Designed to ensure all values in the original ranges are either carried over or mapped into the output,
+/// with each output range keeping track of the seed that maps onto its start
+fn merge_linear_ranges_and_map_tuples(
+    input_ranges: &[TrackedRange],
+    map: &AlmanacMap,
+) -> Result<Vec<TrackedRange>, MergeError> {
+    let mut output_ranges: Vec<TrackedRange> = Vec::new();
+
+    for input_range in input_ranges {
+        for (range, map_tuple) in map.segments(input_range.range.as_range()) {
+            let start = match map_tuple {
+                Some(map_tuple) => map_tuple.calculate_output(range.start)?,
+                None => range.start, // unmapped, so maps to itself
+            };
+            output_ranges.push(TrackedRange {
+                range: LinearRange::new(start, range.len())?,
+                origin: input_range.origin + (range.start - input_range.range.start),
+                seed_set: input_range.seed_set,
+            });
         }
     }
 
-    return Ok(output_tree.get_sorted_vec());
+    // overlapping outputs are kept apart, as each one maps back to different seeds
+    output_ranges.sort_by_key(|tracked_range| tracked_range.range.start);
+    Ok(output_ranges)
 }
 
 #[derive(Debug, Error)]
 enum MergeError {
-    #[error("Overflow in LRBST key")]
-    BinaryTreeOverflow(#[from] LinearRangeBinarySearchTreeError),
-
     #[error("Error in linear range creation: {0}")]
     FailedToCreateLinearRange(#[from] LinearRangeError),
 
     #[error("Error getting output value from MapTuple: {0}")]
     MapTupleOutput(#[from] MapTupleError),
-
-    #[error("`critical_point` is before `current_input`, resulting in a negative usize")]
-    Underflow,
-}
-
-enum CriticalPointEnum {
-    StartOfInputRange(usize),
-    StartOfMapTuple(usize),
-    EndOfInputRange(usize),
-    EndOfMapTuple(usize),
-    StartOfNextInputRange,
-}
-
-/// Evaluates where the next critical point is based on the provided current value, current LinearRange, and current MapTuple
-fn get_critical_point(
-    current_value: usize,
-    range: &LinearRange,
-    map_tuple: &MapTuple,
-) -> CriticalPointEnum {
-    let input_range_end = range.start + range.steps - 1;
-    let map_tuple_end = map_tuple.source_range_start + map_tuple.range_length - 1;
-
-    if current_value < range.start {
-        // Not yet in the seed set
-        CriticalPointEnum::StartOfInputRange(range.start)
-    } else if current_value >= input_range_end {
-        // Beyond the seed set
-        CriticalPointEnum::StartOfNextInputRange
-    } else if current_value < map_tuple.source_range_start {
-        // Inside the seed set, but before the map tuple
-        CriticalPointEnum::StartOfMapTuple(map_tuple.source_range_start)
-    } else if current_value < map_tuple_end {
-        // Inside the map tuple
-        if map_tuple_end > input_range_end {
-            // The map tuple extends beyond the current input range
-            CriticalPointEnum::EndOfInputRange(input_range_end)
-        } else {
-            // The map tuple is contained within the input range
-            CriticalPointEnum::EndOfMapTuple(map_tuple_end)
-        }
-    } else {
-        // Inside the seed set, but after the map tuple
-        CriticalPointEnum::EndOfInputRange(input_range_end)
-    }
 }
 
 struct SeedsAndMaps {
     seeds: Vec<usize>,
-    maps: Vec<MapBinarySearchTree>,
-}
-
-enum SearchDirection {
-    Contains,
-    Greater,
-    Less,
+    almanac: Almanac,
 }
 
 mod seed_set {
-    use std::cmp::Ordering;
-    use thiserror::Error;
-
-    use crate::day_5::SearchDirection;
-
+    #[derive(Debug, Clone, Copy, PartialEq)]
     pub struct SeedSet {
         pub start: usize,
         pub steps: usize,
     }
 
-    #[derive(Debug, Error)]
-    pub enum SeedSetError {
-        #[error("Compared two seed sets that overlap")]
-        Overlap,
-    }
-
     impl SeedSet {
         pub fn new(start: usize, steps: usize) -> Self {
             Self {
@@ -297,36 +431,12 @@ mod seed_set {
         pub fn contains(&self, value: usize) -> bool {
             return self.start <= value && value < self.start + self.steps;
         }
-
-        pub fn does_not_overlap(&self, other: &Self) -> bool {
-            return self.start + self.steps <= other.start
-                || other.start + other.steps <= self.start;
-        }
-
-        pub fn get_search_direction(&self, value: usize) -> SearchDirection {
-            match (self.contains(value), value < self.start) {
-                (true, _) => SearchDirection::Contains,
-                (false, true) => SearchDirection::Less,
-                (false, false) => SearchDirection::Greater,
-            }
-        }
-
-        pub fn compare_without_overlap(&self, other: &Self) -> Result<Ordering, SeedSetError> {
-            if self.does_not_overlap(other) {
-                match self.start < other.start {
-                    true => return Ok(Ordering::Less),
-                    false => return Ok(Ordering::Greater),
-                }
-            } else {
-                return Err(SeedSetError::Overlap);
-            }
-        }
     }
 }
 
 struct SeedSetsAndMaps {
     seed_sets: Vec<SeedSet>,
-    maps: Vec<MapBinarySearchTree>,
+    almanac: Almanac,
 }
 
 fn parse_input_part_1(input: &str) -> Result<SeedsAndMaps, ParseInputError> {
@@ -342,13 +452,13 @@ fn parse_input_part_1(input: &str) -> Result<SeedsAndMaps, ParseInputError> {
 
     let mut rows_iter = rows.into_iter();
     let output_seeds = parse_seeds_part_1(rows_iter.next().unwrap())?;
-    let output_maps = parse_maps(rows_iter.collect::<Vec<&str>>())?;
+    let output_almanac = parse_maps(rows_iter.collect::<Vec<&str>>())?;
 
     println!("Successfully parsed input file!");
 
     return Ok(SeedsAndMaps {
         seeds: output_seeds,
-        maps: output_maps,
+        almanac: output_almanac,
     });
 }
 
@@ -365,13 +475,13 @@ fn parse_input_part_2(input: &str) -> Result<SeedSetsAndMaps, ParseInputError> {
 
     let mut rows_iter = rows.into_iter();
     let output_seed_sets = parse_seeds_part_2(rows_iter.next().unwrap())?;
-    let output_maps = parse_maps(rows_iter.collect::<Vec<&str>>())?;
+    let output_almanac = parse_maps(rows_iter.collect::<Vec<&str>>())?;
 
     println!("Successfully parsed input file!");
 
     return Ok(SeedSetsAndMaps {
         seed_sets: output_seed_sets,
-        maps: output_maps,
+        almanac: output_almanac,
     });
 }
 
@@ -396,7 +506,7 @@ enum SeedParseError {
     RegexError(String),
 
     #[error("Failed to parse seed number: {0}")]
-    InvalidNumber(#[from] ParseIntError),
+    InvalidNumber(#[from] NumberError),
 
     #[error("The 'seeds' keyword is missing or malformed")]
     MissingKeyword,
@@ -417,10 +527,7 @@ fn parse_seeds_part_1(seeds: &str) -> Result<Vec<usize>, SeedParseError> {
         .get(1)
         .ok_or(SeedParseError::MissingKeyword)?
         .as_str();
-    let output: Result<Vec<usize>, ParseIntError> = seeds_str
-        .split_whitespace()
-        .map(|s| s.parse::<usize>())
-        .collect();
+    let output = parse_numbers(seeds_str, Location::Named(String::from("the seeds")));
     match output {
         Ok(result) => {
             println!("Successfully parsed seeds!");
@@ -445,10 +552,7 @@ fn parse_seeds_part_2(seeds: &str) -> Result<Vec<SeedSet>, SeedParseError> {
         .get(1)
         .ok_or(SeedParseError::MissingKeyword)?
         .as_str();
-    let all_seeds_result: Result<Vec<usize>, ParseIntError> = seeds_str
-        .split_whitespace()
-        .map(|s| s.parse::<usize>())
-        .collect();
+    let all_seeds_result = parse_numbers(seeds_str, Location::Named(String::from("the seeds")));
     let all_seeds = match all_seeds_result {
         Ok(result) => {
             println!("Successfully parsed seeds!");
@@ -472,7 +576,7 @@ enum MapParseError {
     RegexError(String),
 
     #[error("Failed to parse mapping number: {0}")]
-    InvalidNumber(#[from] ParseIntError),
+    InvalidNumber(#[from] NumberError),
 
     #[error("Invalid row format")]
     InvalidRowFormat,
@@ -489,26 +593,29 @@ enum MapParseError {
     #[error("Missing mappping row(s) or invalid format")]
     MissingMappingRows,
 
-    #[error("MapBinarySearchTreeError: {0}")]
-    MapBinarySearchTreeError(#[from] MapBinarySearchTreeError),
+    #[error("AlmanacMapError: {0}")]
+    AlmanacMapError(#[from] AlmanacMapError),
+
+    #[error("AlmanacError: {0}")]
+    AlmanacError(#[from] AlmanacError),
 }
 
-fn parse_maps(map_blocks: Vec<&str>) -> Result<Vec<MapBinarySearchTree>, MapParseError> {
+fn parse_maps(map_blocks: Vec<&str>) -> Result<Almanac, MapParseError> {
     let block_pattern = Regex::new(r"(\w+)-to-(\w+) map:\n((?:\d+\s+\d+\s+\d+\n?)*)")
         .map_err(|e| MapParseError::RegexError(e.to_string()))?;
 
     println!("Beginning to parse maps...");
-    let mut maps: Vec<MapBinarySearchTree> = Vec::new();
+    let mut almanac = Almanac::new();
 
     for block in map_blocks {
         let captures = block_pattern
             .captures(block)
             .ok_or(MapParseError::MissingBlock)?;
-        let _input = captures
+        let input = captures
             .get(1)
             .ok_or(MapParseError::MissingInputString)?
             .as_str();
-        let _output = captures
+        let output = captures
             .get(2)
             .ok_or(MapParseError::MissingOutputString)?
             .as_str();
@@ -519,20 +626,21 @@ fn parse_maps(map_blocks: Vec<&str>) -> Result<Vec<MapBinarySearchTree>, MapPars
 
         let rows: Result<Vec<MapTuple>, MapParseError> = rows_str
             .lines()
-            .map(|line| {
+            .enumerate()
+            .map(|(index, line)| {
                 let nums: Vec<&str> = line.split_whitespace().collect();
                 if nums.len() != 3 {
                     return Err(MapParseError::InvalidRowFormat);
                 }
-                let destination_range_start = nums[0]
-                    .parse::<usize>()
-                    .map_err(|e| MapParseError::InvalidNumber(e))?;
-                let source_range_start = nums[1]
-                    .parse::<usize>()
-                    .map_err(|e| MapParseError::InvalidNumber(e))?;
-                let range_length = nums[2]
-                    .parse::<usize>()
-                    .map_err(|e| MapParseError::InvalidNumber(e))?;
+                let location = Location::Named(format!(
+                    "row {} of the {}-to-{} map",
+                    index + 1,
+                    input,
+                    output
+                ));
+                let destination_range_start = parse_number(nums[0], location.clone())?;
+                let source_range_start = parse_number(nums[1], location.clone())?;
+                let range_length = parse_number(nums[2], location)?;
                 Ok(MapTuple::new(
                     destination_range_start,
                     source_range_start,
@@ -543,19 +651,53 @@ fn parse_maps(map_blocks: Vec<&str>) -> Result<Vec<MapBinarySearchTree>, MapPars
 
         let rows = rows?;
 
-        maps.push(
-            MapBinarySearchTree::from_vec(rows)
-                .map_err(|e| MapParseError::MapBinarySearchTreeError(e))?,
-        )
+        let map = AlmanacMap::from_vec(rows).map_err(MapParseError::AlmanacMapError)?;
+        almanac.insert(Category::new(input), Category::new(output), map)?;
     }
     println!("Successfully parsed maps!");
-    return Ok(maps);
+    Ok(almanac)
 }
 
 #[cfg(test)]
 mod tests {
     mod examples {
-        use crate::day_5::solve_part_2;
+        use crate::day_5::{solve_part_1, solve_part_2};
+
+        #[test]
+        fn example_for_part_1() {
+            use std::fs;
+
+            let example_input = match fs::read_to_string("src/day_5/test_input_part_1.txt") {
+                Ok(result) => result,
+                Err(err) => panic!("Error in file reading: {err}"),
+            };
+
+            let answer = match solve_part_1(&example_input) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in solving part 1 example: {err}"),
+            };
+
+            assert_eq!(answer, 35)
+        }
+
+        #[test]
+        fn example_for_part_1_with_maps_out_of_order() {
+            use std::fs;
+
+            let example_input = match fs::read_to_string("src/day_5/test_input_part_1.txt") {
+                Ok(result) => result,
+                Err(err) => panic!("Error in file reading: {err}"),
+            };
+            let mut blocks = example_input.split("\n\n").collect::<Vec<_>>();
+            blocks[1..].reverse();
+
+            let answer = match solve_part_1(&blocks.join("\n\n")) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in solving part 1 example: {err}"),
+            };
+
+            assert_eq!(answer, 35)
+        }
 
         #[test]
         fn example_for_part_2() {
@@ -573,34 +715,367 @@ mod tests {
 
             assert_eq!(answer, 46)
         }
+
+        #[test]
+        fn witnesses_for_example() {
+            use crate::day_5::{
+                find_part_1_witness, find_part_2_witness, get_seed_to_location_map,
+                parse_input_part_1, seed_set::SeedSet,
+            };
+            use std::fs;
+
+            let example_input = match fs::read_to_string("src/day_5/test_input_part_1.txt") {
+                Ok(result) => result,
+                Err(err) => panic!("Error in file reading: {err}"),
+            };
+
+            let part_1 = match find_part_1_witness(&example_input) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in part 1 witness: {err}"),
+            };
+            let part_2 = match find_part_2_witness(&example_input) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in part 2 witness: {err}"),
+            };
+
+            assert_eq!((part_1.seed, part_1.location), (13, 35));
+            assert_eq!((part_2.seed, part_2.location), (82, 46));
+            assert_eq!(part_2.seed_set, Some(SeedSet::new(79, 14)));
+
+            // cross-check the part 2 seed with part 1's point mapping
+            let location_map = match parse_input_part_1(&example_input) {
+                Ok(result) => match get_seed_to_location_map(&result.almanac) {
+                    Ok(map) => map,
+                    Err(err) => panic!("Error in get_seed_to_location_map: {err}"),
+                },
+                Err(err) => panic!("Error in parsing example: {err}"),
+            };
+            assert_eq!(location_map.get_mapped_value(part_2.seed), part_2.location);
+            assert!(SeedSet::new(79, 14).contains(part_2.seed));
+        }
     }
 
     mod merge_linear_ranges_and_map_tuples {
         use crate::day_5::{
-            linear_range::LinearRange, map_tuple::MapTuple, merge_linear_ranges_and_map_tuples,
+            almanac_map::AlmanacMap, linear_range::TrackedRange, map_tuple::MapTuple,
+            merge_linear_ranges_and_map_tuples, seed_set::SeedSet,
         };
 
         #[test]
         fn first_example_from_part_2() {
             let seeds = vec![
-                match LinearRange::new(55, 13) {
+                match TrackedRange::from_seed_set(&SeedSet::new(55, 13)) {
                     Ok(result) => result,
                     Err(e) => panic!("This should never happen: {e}"),
                 },
-                match LinearRange::new(79, 25) {
+                match TrackedRange::from_seed_set(&SeedSet::new(79, 25)) {
                     Ok(result) => result,
                     Err(e) => panic!("This should never happen: {e}"),
                 },
             ];
 
-            let seed_to_soil_maps = vec![MapTuple::new(52, 50, 48), MapTuple::new(50, 98, 2)];
+            let seed_to_soil_map = match AlmanacMap::from_vec(vec![
+                MapTuple::new(52, 50, 48),
+                MapTuple::new(50, 98, 2),
+            ]) {
+                Ok(result) => result,
+                Err(e) => panic!("Error building map: {e}"),
+            };
 
-            let output = match merge_linear_ranges_and_map_tuples(&seeds, &seed_to_soil_maps) {
+            let output = match merge_linear_ranges_and_map_tuples(&seeds, &seed_to_soil_map) {
                 Ok(result) => result,
                 Err(e) => panic!("Error in merge: {e}"),
             };
 
-            print!("{:?}", output)
+            print!("{:?}", output);
+            assert_eq!(
+                output
+                    .iter()
+                    .map(|tracked_range| (tracked_range.range.start, tracked_range.range.steps))
+                    .collect::<Vec<_>>(),
+                vec![(50, 2), (57, 13), (81, 19), (100, 4)]
+            );
+            assert_eq!(
+                output
+                    .iter()
+                    .map(|tracked_range| (tracked_range.origin, tracked_range.seed_set.start))
+                    .collect::<Vec<_>>(),
+                vec![(98, 79), (55, 55), (79, 79), (100, 79)]
+            )
+        }
+    }
+
+    mod compose_chain {
+        use crate::day_5::{
+            almanac::Category, almanac_map::AlmanacMap, map_tuple::MapTuple, parse_input_part_1,
+        };
+
+        #[test]
+        fn matches_applying_each_map_in_turn() {
+            use std::fs;
+
+            let example_input = match fs::read_to_string("src/day_5/test_input_part_1.txt") {
+                Ok(result) => result,
+                Err(err) => panic!("Error in file reading: {err}"),
+            };
+            let parsed_input = match parse_input_part_1(&example_input) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in parsing example: {err}"),
+            };
+            let maps = match parsed_input
+                .almanac
+                .chain(&Category::new("seed"), &Category::new("location"))
+            {
+                Ok(result) => result,
+                Err(err) => panic!("Error in chain: {err}"),
+            };
+            let location_map = match AlmanacMap::compose_chain(maps.iter().map(|link| link.map)) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in compose_chain: {err}"),
+            };
+
+            assert_eq!(maps.len(), 7);
+            for seed in 0..120 {
+                let expected = maps
+                    .iter()
+                    .fold(seed, |current, link| link.map.get_mapped_value(current));
+                assert_eq!(location_map.get_mapped_value(seed), expected, "seed {seed}");
+            }
+        }
+
+        #[test]
+        fn prints_in_almanac_format() {
+            let first = match AlmanacMap::from_vec(vec![
+                MapTuple::new(52, 50, 48),
+                MapTuple::new(50, 98, 2),
+            ]) {
+                Ok(result) => result,
+                Err(err) => panic!("Error building map: {err}"),
+            };
+            let second = match AlmanacMap::from_vec(vec![
+                MapTuple::new(0, 15, 37),
+                MapTuple::new(37, 52, 2),
+                MapTuple::new(39, 0, 15),
+            ]) {
+                Ok(result) => result,
+                Err(err) => panic!("Error building map: {err}"),
+            };
+
+            let composed = match AlmanacMap::compose_chain(&[first, second]) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in compose_chain: {err}"),
+            };
+
+            assert_eq!(
+                composed.to_string(),
+                "39 0 15\n0 15 35\n37 50 2\n54 52 46\n35 98 2\n"
+            );
+        }
+    }
+
+    mod preimage {
+        use crate::day_5::{
+            almanac_map::AlmanacMap, get_seed_to_location_map, linear_range::LinearRange,
+            lowest_location_searching_upward, map_tuple::MapTuple, merge_seed_ranges,
+            parse_input_part_2,
+        };
+        use std::ops::Range;
+
+        fn example_location_map() -> (AlmanacMap, Vec<Range<usize>>) {
+            use std::fs;
+
+            let example_input = match fs::read_to_string("src/day_5/test_input_part_1.txt") {
+                Ok(result) => result,
+                Err(err) => panic!("Error in file reading: {err}"),
+            };
+            let parsed_input = match parse_input_part_2(&example_input) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in parsing example: {err}"),
+            };
+            let location_map = match get_seed_to_location_map(&parsed_input.almanac) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in get_seed_to_location_map: {err}"),
+            };
+            let seed_ranges = parsed_input
+                .seed_sets
+                .iter()
+                .map(|seed_set| match LinearRange::from_seed_set(seed_set) {
+                    Ok(result) => result,
+                    Err(err) => panic!("Error in seed range: {err}"),
+                })
+                .collect::<Vec<LinearRange>>();
+            let seed_ranges = match merge_seed_ranges(&seed_ranges) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in merge_seed_ranges: {err}"),
+            };
+            (location_map, seed_ranges)
+        }
+
+        #[test]
+        fn finds_every_seed_for_a_location() {
+            let (location_map, _) = example_location_map();
+
+            // the worked example has seed 82 landing in location 46
+            assert!(location_map.preimage_of_value(46).unwrap().contains(&82));
+            for location in 0..120 {
+                for seed in location_map.preimage_of_value(location).unwrap() {
+                    assert_eq!(location_map.get_mapped_value(seed), location);
+                }
+            }
+        }
+
+        #[test]
+        fn returns_overlapping_preimages_of_a_range() {
+            // 0..10 maps onto 20..30, which is also where the unmapped 20..30 lands
+            let map = match AlmanacMap::from_vec(vec![MapTuple::new(20, 0, 10)]) {
+                Ok(result) => result,
+                Err(err) => panic!("Error building map: {err}"),
+            };
+
+            let sources = map
+                .preimage(25..35)
+                .iter()
+                .map(|map_tuple| map_tuple.source_range())
+                .collect::<Vec<_>>();
+
+            assert_eq!(sources, vec![5..10, 25..35]);
+            assert!(map.preimage_of_value(usize::MAX).is_err());
+        }
+
+        #[test]
+        fn merges_overlapping_seed_ranges() {
+            let seed_ranges = [
+                LinearRange::new(10, 10).unwrap(),
+                LinearRange::new(0, 5).unwrap(),
+                LinearRange::new(15, 10).unwrap(),
+                LinearRange::new(5, 5).unwrap(),
+            ];
+
+            assert_eq!(
+                merge_seed_ranges(&seed_ranges),
+                Ok(vec![0..5, 5..10, 10..25])
+            );
+        }
+
+        #[test]
+        fn searching_upward_agrees_with_part_2() {
+            let (location_map, seed_ranges) = example_location_map();
+
+            assert_eq!(
+                lowest_location_searching_upward(&location_map, &seed_ranges),
+                Some(46)
+            );
+        }
+    }
+
+    mod trace {
+        use crate::day_5::{
+            almanac::Category, parse_input_part_1, parse_value_range, trace::trace,
+        };
+
+        fn example_trace(values: std::ops::Range<usize>) -> crate::day_5::SeedTrace {
+            use std::fs;
+
+            let example_input = match fs::read_to_string("src/day_5/test_input_part_1.txt") {
+                Ok(result) => result,
+                Err(err) => panic!("Error in file reading: {err}"),
+            };
+            let parsed_input = match parse_input_part_1(&example_input) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in parsing example: {err}"),
+            };
+            match trace(
+                &parsed_input.almanac,
+                &Category::new("seed"),
+                &Category::new("location"),
+                values,
+            ) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in trace: {err}"),
+            }
+        }
+
+        #[test]
+        fn follows_seed_79_from_the_example() {
+            let seed_trace = example_trace(79..80);
+
+            let values = seed_trace
+                .steps
+                .iter()
+                .map(|step| step.pieces[0].output.start)
+                .collect::<Vec<_>>();
+            let unmapped = seed_trace
+                .steps
+                .iter()
+                .map(|step| step.pieces[0].map_tuple.is_none())
+                .collect::<Vec<_>>();
+
+            assert_eq!(values, vec![81, 81, 81, 74, 78, 78, 82]);
+            assert_eq!(unmapped, vec![false, true, true, false, false, true, false]);
+            assert_eq!(seed_trace.outputs(), vec![82..83]);
+            assert!(seed_trace
+                .to_string()
+                .starts_with("seed 79\nseed-to-soil:\n  79 -> 81 via `52 50 48`\nsoil-to-fertilizer:\n  81 -> 81 unmapped\n"));
+        }
+
+        #[test]
+        fn splits_ranges_across_map_tuples() {
+            let seed_trace = example_trace(90..100);
+
+            let seed_to_soil = &seed_trace.steps[0];
+            assert_eq!(seed_to_soil.pieces.len(), 2);
+            assert_eq!(seed_to_soil.pieces[0].input, 90..98);
+            assert_eq!(seed_to_soil.pieces[0].output, 92..100);
+            assert_eq!(seed_to_soil.pieces[1].input, 98..100);
+            assert_eq!(seed_to_soil.pieces[1].output, 50..52);
+        }
+
+        #[test]
+        fn parses_values_and_ranges() {
+            assert_eq!(parse_value_range("79"), Ok(79..80));
+            assert_eq!(parse_value_range("79..93"), Ok(79..93));
+            assert!(parse_value_range("79..").is_err());
+            assert!(parse_value_range(&usize::MAX.to_string()).is_err());
+        }
+    }
+
+    mod normalise_almanac {
+        use crate::day_5::{almanac_file::AlmanacFile, solve_part_1, solve_part_2};
+
+        #[test]
+        fn normalised_input_gives_the_same_answers() {
+            use std::fs;
+
+            let input = match fs::read_to_string("src/day_5/input.txt") {
+                Ok(result) => result,
+                Err(err) => panic!("Error in file reading: {err}"),
+            };
+            let normalised = match AlmanacFile::parse(&input) {
+                Ok(result) => result.normalise().to_string(),
+                Err(err) => panic!("Error parsing almanac: {err}"),
+            };
+
+            let answers = |input: &str| match (solve_part_1(input), solve_part_2(input)) {
+                (Ok(part_1), Ok(part_2)) => (part_1, part_2),
+                (Err(err), _) | (_, Err(err)) => panic!("Error in solving: {err}"),
+            };
+            assert_eq!(answers(&normalised), answers(&input));
+        }
+    }
+
+    mod print_seed_to_location_map {
+        use crate::day_5::print_seed_to_location_map;
+
+        #[test]
+        fn prints_the_composed_map_as_almanac_rows() {
+            let output = match print_seed_to_location_map("src/day_5/test_input_part_1.txt") {
+                Ok(result) => result,
+                Err(err) => panic!("Error in printing the map: {err}"),
+            };
+
+            assert!(output.starts_with("seed-to-location map:\n"));
+            // seeds 82 to 91 all land on locations 46 to 55, as in the puzzle's example
+            assert!(output.lines().any(|line| line == "46 82 10"));
         }
     }
 }
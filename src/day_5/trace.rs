@@ -0,0 +1,136 @@
+use crate::day_5::{
+    almanac::{Almanac, AlmanacError, Category},
+    map_tuple::MapTuple,
+};
+use std::{fmt, ops::Range};
+use thiserror::Error;
+
+/// the path of a value or range of values through each map between two categories
+#[derive(Debug)]
+pub struct SeedTrace {
+    pub category: Category,
+    pub values: Range<usize>,
+    pub steps: Vec<TraceStep>,
+}
+
+/// how every range held before one `x-to-y` map came out of it
+#[derive(Debug)]
+pub struct TraceStep {
+    pub source: Category,
+    pub destination: Category,
+    pub pieces: Vec<TracePiece>,
+}
+
+/// a part of the input to a step that was handled by a single MapTuple, or passed through unmapped (`None`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracePiece {
+    pub input: Range<usize>,
+    pub output: Range<usize>,
+    pub map_tuple: Option<MapTuple>,
+}
+
+#[derive(Debug, Error)]
+pub enum TraceError {
+    #[error("Cannot trace an empty range of values")]
+    EmptyRange,
+
+    #[error("Error following the almanac: {0}")]
+    Almanac(#[from] AlmanacError),
+}
+
+impl SeedTrace {
+    /// the ranges the traced values end up in after the final step
+    pub fn outputs(&self) -> Vec<Range<usize>> {
+        match self.steps.last() {
+            Some(step) => step
+                .pieces
+                .iter()
+                .map(|piece| piece.output.clone())
+                .collect(),
+            None => vec![self.values.clone()],
+        }
+    }
+}
+
+/// follows `values` in category `from` through every map until they reach category `to`,
+/// recording which MapTuple (if any) handled each piece along the way
+pub fn trace(
+    almanac: &Almanac,
+    from: &Category,
+    to: &Category,
+    values: Range<usize>,
+) -> Result<SeedTrace, TraceError> {
+    if values.is_empty() {
+        return Err(TraceError::EmptyRange);
+    }
+
+    let mut current_ranges = vec![values.clone()];
+    let mut steps: Vec<TraceStep> = Vec::new();
+    for link in almanac.chain(from, to)? {
+        let pieces: Vec<TracePiece> = current_ranges
+            .iter()
+            .flat_map(|range| link.map.segments(range.clone()))
+            .map(|(input, map_tuple)| {
+                let start = link.map.get_mapped_value(input.start);
+                TracePiece {
+                    output: start..start + input.len(),
+                    input,
+                    map_tuple: map_tuple.cloned(),
+                }
+            })
+            .collect();
+        current_ranges = pieces.iter().map(|piece| piece.output.clone()).collect();
+        steps.push(TraceStep {
+            source: link.source.clone(),
+            destination: link.destination.clone(),
+            pieces,
+        });
+    }
+
+    Ok(SeedTrace {
+        category: from.clone(),
+        values,
+        steps,
+    })
+}
+
+/// helper function to show single values without range syntax
+fn format_range(range: &Range<usize>) -> String {
+    match range.len() {
+        1 => range.start.to_string(),
+        _ => format!("{}..{}", range.start, range.end),
+    }
+}
+
+impl fmt::Display for SeedTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", self.category, format_range(&self.values))?;
+        for step in self.steps.iter() {
+            writeln!(f, "{}-to-{}:", step.source, step.destination)?;
+            for piece in step.pieces.iter() {
+                match &piece.map_tuple {
+                    Some(map_tuple) => writeln!(
+                        f,
+                        "  {} -> {} via `{} {} {}`",
+                        format_range(&piece.input),
+                        format_range(&piece.output),
+                        map_tuple.destination_range_start,
+                        map_tuple.source_range_start,
+                        map_tuple.range_length
+                    )?,
+                    None => writeln!(
+                        f,
+                        "  {} -> {} unmapped",
+                        format_range(&piece.input),
+                        format_range(&piece.output)
+                    )?,
+                }
+            }
+        }
+        if let Some(step) = self.steps.last() {
+            let outputs = self.outputs().iter().map(format_range).collect::<Vec<_>>();
+            writeln!(f, "{} {}", step.destination, outputs.join(", "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+use crate::day_6::{race_model::RaceModel, RaceInfo};
+use std::collections::{BTreeSet, BinaryHeap};
+
+/// picks at most `max_samples` hold times to plot a race with, paired with their distances.
+///
+/// short races get every hold time. for longer ones, the start, end, peak and the edges of the winning interval
+/// (plus where the curve bends at the charge delay and speed cap) are always kept, and then the stretch between
+/// neighbouring samples that a straight line fits worst is split in half, until the budget runs out or every
+/// stretch is already straight to within a thousandth of the tallest distance
+pub fn sample_distances(
+    model: &RaceModel,
+    race_info: &RaceInfo,
+    max_samples: usize,
+) -> Vec<(u128, u128)> {
+    let time = race_info.time;
+    let distance = |hold: u128| model.distance(time, hold);
+    if time < max_samples as u128 {
+        return (0..=time).map(|hold| (hold, distance(hold))).collect();
+    }
+
+    let outcome = model.solve(race_info);
+    let mut holds: BTreeSet<u128> = BTreeSet::from([0, time, outcome.optimal_hold]);
+    if let Some(winning_holds) = &outcome.winning_holds {
+        for hold in [
+            winning_holds.start().saturating_sub(1),
+            *winning_holds.start(),
+            *winning_holds.end(),
+            winning_holds.end().saturating_add(1),
+        ] {
+            holds.insert(hold.min(time));
+        }
+    }
+    for hold in [Some(model.charge_delay), model.capped_from()]
+        .into_iter()
+        .flatten()
+    {
+        holds.insert(hold.min(time));
+    }
+
+    // how far the curve at the midpoint of a stretch is from the straight line across it
+    let error = |start: u128, end: u128| {
+        let middle = start + (end - start) / 2;
+        distance(middle).abs_diff(distance(start) / 2 + distance(end) / 2)
+    };
+    let tolerance = outcome.optimal_distance.max(race_info.distance_to_beat) / 1000;
+    let mut stretches: BinaryHeap<(u128, u128, u128)> = holds
+        .iter()
+        .zip(holds.iter().skip(1))
+        .filter(|(start, end)| *end - *start > 1)
+        .map(|(&start, &end)| (error(start, end), start, end))
+        .collect();
+    while holds.len() < max_samples {
+        let Some((worst, start, end)) = stretches.pop() else {
+            break;
+        };
+        if worst <= tolerance {
+            break;
+        }
+        let middle = start + (end - start) / 2;
+        holds.insert(middle);
+        for (start, end) in [(start, middle), (middle, end)] {
+            if end - start > 1 {
+                stretches.push((error(start, end), start, end));
+            }
+        }
+    }
+
+    holds
+        .into_iter()
+        .map(|hold| (hold, distance(hold)))
+        .collect()
+}
+
+/// helper function to label a chart, and to pick the tallest distance it needs to show
+fn chart_bounds(model: &RaceModel, race_info: &RaceInfo) -> (f64, f64) {
+    let outcome = model.solve(race_info);
+    let max_distance = outcome
+        .optimal_distance
+        .max(race_info.distance_to_beat)
+        .max(1);
+    (race_info.time.max(1) as f64, max_distance as f64)
+}
+
+/// draws distance against hold time as text: `*` for the curve, `-` for the record,
+/// and `#` shading the winning holds between the record and the curve.
+/// each column is worked out exactly from the model, so the cost only depends on the size of the chart
+pub fn render_ascii(
+    model: &RaceModel,
+    race_info: &RaceInfo,
+    width: usize,
+    height: usize,
+) -> String {
+    let (width, height) = (width.max(2), height.max(2));
+    let (max_hold, max_distance) = chart_bounds(model, race_info);
+    let row_of = |distance: f64| {
+        let row = (height - 1) as f64 * (1.0 - distance / max_distance);
+        (row.round() as usize).min(height - 1)
+    };
+    let record_row = row_of(race_info.distance_to_beat as f64);
+
+    let columns: Vec<(usize, bool)> = (0..width)
+        .map(|column| {
+            let hold = ((column as f64 / (width - 1) as f64) * max_hold) as u128;
+            let hold = hold.min(race_info.time);
+            let distance = model.distance(race_info.time, hold);
+            (
+                row_of(distance as f64),
+                distance > race_info.distance_to_beat,
+            )
+        })
+        .collect();
+
+    let labels = [
+        (0, format!("{}", max_distance as u128)),
+        (record_row, race_info.distance_to_beat.to_string()),
+        (height - 1, "0".to_string()),
+    ];
+    let label_width = labels
+        .iter()
+        .map(|(_, label)| label.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut output = format!(
+        "{} ms race, record {} mm\n",
+        race_info.time, race_info.distance_to_beat
+    );
+    for row in 0..height {
+        let label = labels
+            .iter()
+            .rev()
+            .find(|(label_row, _)| *label_row == row)
+            .map_or("", |(_, label)| label.as_str());
+        output.push_str(&format!("{label:>label_width$} |"));
+        for (curve_row, wins) in columns.iter() {
+            let cell = if row == *curve_row {
+                '*'
+            } else if row == record_row {
+                '-'
+            } else if *wins && row > *curve_row && row < record_row {
+                '#'
+            } else {
+                ' '
+            };
+            output.push(cell);
+        }
+        output.push('\n');
+    }
+    output.push_str(&format!("{:label_width$} +{}\n", "", "-".repeat(width)));
+    let end_label = race_info.time.to_string();
+    output.push_str(&format!(
+        "{:label_width$}  0{}{}\n",
+        "",
+        " ".repeat(width.saturating_sub(1 + end_label.len())),
+        end_label
+    ));
+    output
+}
+
+/// draws distance against hold time as a standalone SVG document, with the record as a dashed line and the
+/// winning holds shaded. the curve is a polyline through `sample_distances`, so huge races stay small and fast
+pub fn render_svg(model: &RaceModel, race_info: &RaceInfo, width: usize, height: usize) -> String {
+    const MARGIN: f64 = 40.0;
+    const MAX_SAMPLES: usize = 512;
+    let (width, height) = (width.max(100) as f64, height.max(100) as f64);
+    let (max_hold, max_distance) = chart_bounds(model, race_info);
+    let x_of = |hold: f64| MARGIN + (width - 2.0 * MARGIN) * hold / max_hold;
+    let y_of = |distance: f64| height - MARGIN - (height - 2.0 * MARGIN) * distance / max_distance;
+
+    let mut output = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+    );
+    output.push_str(&format!(
+        "  <rect width=\"{width}\" height=\"{height}\" fill=\"white\"/>\n"
+    ));
+    if let Some(winning_holds) = model.solve(race_info).winning_holds {
+        let (left, right) = (
+            x_of(*winning_holds.start() as f64),
+            x_of(*winning_holds.end() as f64),
+        );
+        output.push_str(&format!(
+            "  <rect class=\"winning\" x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"green\" fill-opacity=\"0.15\"/>\n",
+            left,
+            MARGIN,
+            (right - left).max(1.0),
+            height - 2.0 * MARGIN
+        ));
+    }
+    let points = sample_distances(model, race_info, MAX_SAMPLES)
+        .iter()
+        .map(|(hold, distance)| format!("{:.2},{:.2}", x_of(*hold as f64), y_of(*distance as f64)))
+        .collect::<Vec<_>>();
+    output.push_str(&format!(
+        "  <polyline class=\"distance\" fill=\"none\" stroke=\"black\" points=\"{}\"/>\n",
+        points.join(" ")
+    ));
+    let record_y = y_of(race_info.distance_to_beat as f64);
+    output.push_str(&format!(
+        "  <line class=\"record\" x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"red\" stroke-dasharray=\"6 4\"/>\n",
+        MARGIN,
+        record_y,
+        width - MARGIN,
+        record_y
+    ));
+    output.push_str(&format!(
+        "  <text x=\"{MARGIN}\" y=\"{:.2}\" font-size=\"12\">{} ms race, record {} mm</text>\n",
+        MARGIN / 2.0,
+        race_info.time,
+        race_info.distance_to_beat
+    ));
+    output.push_str(&format!(
+        "  <text x=\"{MARGIN}\" y=\"{:.2}\" font-size=\"12\">0</text>\n",
+        height - MARGIN / 2.0
+    ));
+    output.push_str(&format!(
+        "  <text x=\"{:.2}\" y=\"{:.2}\" font-size=\"12\" text-anchor=\"end\">{} ms held</text>\n",
+        width - MARGIN,
+        height - MARGIN / 2.0,
+        race_info.time
+    ));
+    output.push_str("</svg>\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_ascii, render_svg, sample_distances};
+    use crate::day_6::{race_model::RaceModel, RaceInfo};
+
+    #[test]
+    fn draws_the_example_race_as_text() {
+        let race_info = RaceInfo {
+            time: 7,
+            distance_to_beat: 9,
+        };
+
+        let chart = render_ascii(&RaceModel::default(), &race_info, 8, 7);
+
+        assert_eq!(
+            chart,
+            [
+                "7 ms race, record 9 mm",
+                "12 |   **   ",
+                "   |  *##*  ",
+                " 9 |--------",
+                "   | *    * ",
+                "   |        ",
+                "   |        ",
+                " 0 |*      *",
+                "   +--------",
+                "    0      7",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn samples_every_hold_of_a_short_race() {
+        let race_info = RaceInfo {
+            time: 30,
+            distance_to_beat: 200,
+        };
+
+        let samples = sample_distances(&RaceModel::default(), &race_info, 100);
+
+        assert_eq!(samples.len(), 31);
+        assert_eq!(samples[10], (10, 200));
+    }
+
+    #[test]
+    fn samples_huge_races_within_budget() {
+        let race_info = RaceInfo {
+            time: 61709066,
+            distance_to_beat: 643118413621041,
+        };
+
+        let samples = sample_distances(&RaceModel::default(), &race_info, 200);
+
+        assert!(samples.len() <= 200);
+        let holds: Vec<u128> = samples.iter().map(|(hold, _)| *hold).collect();
+        // the edges of the winning interval are always sampled
+        assert!(holds.contains(&13279442) && holds.contains(&13279443));
+        assert!(holds.contains(&48429623) && holds.contains(&48429624));
+        assert!(holds.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let svg = render_svg(&RaceModel::default(), &race_info, 640, 360);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains("class=\"winning\"") && svg.contains("class=\"record\""));
+    }
+}
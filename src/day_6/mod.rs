@@ -1,6 +1,14 @@
-use crate::shared::DayResult;
+use crate::shared::{Answer, BigUint, DayResult};
+use race_parser::parse_race_sheet;
 use std::fs;
 
+mod chart;
+mod race_model;
+mod race_parser;
+
+pub use chart::{render_ascii, render_svg};
+pub use race_model::RaceModel;
+
 pub fn solve() -> Result<DayResult, String> {
     let input = match fs::read_to_string("src/day_6/input.txt") {
         Ok(result) => result,
@@ -14,69 +22,245 @@ pub fn solve() -> Result<DayResult, String> {
     return Ok(output);
 }
 
-fn solve_part_1(_input: &str) -> Result<usize, String> {
-    let input = vec![
-        RaceInfo {
-            time: 61,
-            distance_to_beat: 643,
-        },
-        RaceInfo {
-            time: 70,
-            distance_to_beat: 1184,
-        },
-        RaceInfo {
-            time: 90,
-            distance_to_beat: 1362,
-        },
-        RaceInfo {
-            time: 66,
-            distance_to_beat: 1041,
-        },
-    ];
-    let solution_counts = input
-        .iter()
-        .map(|race_info| solutions_to_race_info(race_info))
-        .collect::<Result<Vec<usize>, String>>()?;
-    let output = solution_counts.iter().fold(1, |product, next| product * next);
-    return Ok(output)
+/// reads every race on the puzzle input with a name for it, the kerned race last
+pub fn named_races() -> Result<Vec<(String, RaceInfo)>, String> {
+    let input = match fs::read_to_string("src/day_6/input.txt") {
+        Ok(result) => result,
+        Err(err) => return Err(format!("Error in reading file: {}", err)),
+    };
+    let race_sheet = parse_race_sheet(&input).map_err(|e| e.to_string())?;
+
+    let mut output: Vec<(String, Race)> = race_sheet
+        .races
+        .into_iter()
+        .enumerate()
+        .map(|(index, race)| (format!("race {}", index + 1), race))
+        .collect();
+    output.push(("kerned race".to_string(), race_sheet.kerned_race));
+    output
+        .into_iter()
+        .map(|(name, race)| match race {
+            Race::Small(race_info) => Ok((name, race_info)),
+            Race::Big(_) => Err(format!(
+                "The {} does not fit in 128 bits, so it can only be solved, not explored",
+                name
+            )),
+        })
+        .collect()
+}
+
+/// solves every race on the puzzle input under a different `RaceModel`, one line per race with the kerned race last
+pub fn explore(model: &RaceModel) -> Result<String, String> {
+    let mut output = String::new();
+    for (name, race_info) in named_races()?.iter() {
+        output.push_str(&explore_race(model, name, race_info));
+    }
+    Ok(output)
 }
 
-fn solve_part_2(input: &str) -> Result<usize, String> {
-    let one_big_race = RaceInfo {
-        time: 61709066,
-        distance_to_beat: 643118413621041,
+/// helper function for one line of `explore`: what the exact and the numeric model each say,
+/// and whether they disagree on which whole-millisecond holds win
+fn explore_race(model: &RaceModel, name: &str, race_info: &RaceInfo) -> String {
+    let outcome = model.solve(race_info);
+    let numeric = model.solve_numeric(race_info);
+    let exact = match &outcome.winning_holds {
+        Some(holds) => format!(
+            "holds {}..={} win ({} ways)",
+            holds.start(),
+            holds.end(),
+            holds.end() - holds.start() + 1
+        ),
+        None => "no hold wins".to_string(),
+    };
+    let continuous = match numeric.winning_holds {
+        Some((lower, upper)) => format!("{:.3}..{:.3} wins continuously", lower, upper),
+        None => "nothing wins continuously".to_string(),
+    };
+    let mismatch = match numeric.whole_holds() {
+        whole_holds if whole_holds == outcome.winning_holds => String::new(),
+        Some(holds) => format!(
+            "; MISMATCH: the numeric model gives holds {}..={}",
+            holds.start(),
+            holds.end()
+        ),
+        None => "; MISMATCH: the numeric model gives no whole hold".to_string(),
     };
-    let solution_count = solutions_to_race_info(&one_big_race)?;
+    format!(
+        "{} ({} ms, record {} mm): exact {}, numeric {}{}; best hold {} ms goes {} mm, {}\n",
+        name,
+        race_info.time,
+        race_info.distance_to_beat,
+        exact,
+        continuous,
+        mismatch,
+        outcome.optimal_hold,
+        outcome.optimal_distance,
+        match outcome.margin {
+            Some(margin) => format!("{} mm past the record", margin),
+            None => "short of the record".to_string(),
+        }
+    )
+}
+
+fn solve_part_1(input: &str) -> Result<Answer, String> {
+    let race_sheet = parse_race_sheet(input).map_err(|e| e.to_string())?;
+    let output = race_sheet.races.iter().map(solutions_to_race).product();
+    Ok(output)
+}
+
+fn solve_part_2(input: &str) -> Result<Answer, String> {
+    let race_sheet = parse_race_sheet(input).map_err(|e| e.to_string())?;
+    let solution_count = solutions_to_race(&race_sheet.kerned_race);
 
     return Ok(solution_count);
 }
 
-struct RaceInfo {
-    time: usize,
-    distance_to_beat: usize,
+#[derive(Debug, PartialEq)]
+pub struct RaceInfo {
+    pub time: u128,
+    pub distance_to_beat: u128,
 }
 
-fn solutions_to_race_info(race_info: &RaceInfo) -> Result<usize, String> {
-    let solutions = solve_quadratic(race_info.time, race_info.distance_to_beat)?;
-    let output = (solutions.1 as usize)
-        .checked_sub(solutions.0 as usize)
-        .ok_or("No solutions found")?;
-    Ok(output)
+/// a race whose time or distance does not fit in 128 bits
+#[derive(Debug, PartialEq)]
+pub struct BigRaceInfo {
+    pub time: BigUint,
+    pub distance_to_beat: BigUint,
 }
 
-fn solve_quadratic(b: usize, c: usize) -> Result<(f64, f64), String> {
-    // solve (T-x)x - D = 0
-    // Tx - x^2 - D
-    let discriminant = (b * b)
-        .checked_sub(4 * c)
-        .ok_or("Discriminant less than 0")?;
-    let small_solution = ((b as f64) - (discriminant as f64).sqrt()) / 2.0;
-    let large_solution = ((b as f64) + (discriminant as f64).sqrt()) / 2.0;
-    Ok((small_solution, large_solution))
+/// a race off the sheet, kept in 128 bits whenever it fits
+#[derive(Debug, PartialEq)]
+pub enum Race {
+    Small(RaceInfo),
+    Big(BigRaceInfo),
+}
+
+/// the number of ways to win either kind of race
+fn solutions_to_race(race: &Race) -> Answer {
+    match race {
+        Race::Small(race_info) => solutions_to_race_info(race_info),
+        Race::Big(race_info) => Answer::from(count_winning_holds_big(
+            &race_info.time,
+            &race_info.distance_to_beat,
+        )),
+    }
+}
+
+/// the number of ways to win, which is only a `BigUint` if it does not fit in a `usize`
+fn solutions_to_race_info(race_info: &RaceInfo) -> Answer {
+    let count = count_winning_holds(race_info.time, race_info.distance_to_beat);
+    Answer::from(BigUint::from(count))
+}
+
+/// counts the whole-millisecond hold times `h` that beat the record, i.e. `h * (time - h) > distance`.
+/// exact: a hold that only ties the record does not count.
+/// works in checked 128-bit arithmetic, and falls back to `BigUint` if any step would overflow
+pub fn count_winning_holds(time: u128, distance: u128) -> u128 {
+    match count_winning_holds_checked(time, distance) {
+        Some(count) => count,
+        None => count_winning_holds_big(&BigUint::from(time), &BigUint::from(distance))
+            .to_u128()
+            .expect("there are fewer ways to win than hold times"),
+    }
+}
+
+/// helper function for `count_winning_holds`, returning `None` on overflow
+fn count_winning_holds_checked(time: u128, distance: u128) -> Option<u128> {
+    // the winning holds lie strictly between the roots of h^2 - Th + D = 0
+    let time_squared = time.checked_mul(time)?;
+    let four_distance = distance.checked_mul(4)?;
+    if time_squared <= four_distance {
+        return Some(0); // at best the hold at T/2 ties the record
+    }
+    let root = (time_squared - four_distance).isqrt();
+
+    // (T - root) / 2 is at most one step below the first winning hold, and never above it
+    let mut hold = (time - root) / 2;
+    while hold <= time / 2 && hold * (time - hold) <= distance {
+        hold += 1;
+    }
+    if hold > time / 2 {
+        return Some(0);
+    }
+    // the winning holds are symmetric about T/2, from `hold` up to `time - hold`
+    Some(time - 2 * hold + 1)
+}
+
+/// the same count as `count_winning_holds`, for races whose time or distance do not fit in 128 bits
+pub fn count_winning_holds_big(time: &BigUint, distance: &BigUint) -> BigUint {
+    let one = BigUint::from(1);
+    let time_squared = time * time;
+    let four_distance = distance.clone() << 2;
+    let discriminant = match time_squared.checked_sub(&four_distance) {
+        Some(discriminant) if !discriminant.is_zero() => discriminant,
+        _ => return BigUint::zero(),
+    };
+    let root = discriminant.isqrt();
+
+    let half_time = time >> 1;
+    let mut hold = &time
+        .checked_sub(&root)
+        .expect("the root of the discriminant is at most the time")
+        >> 1;
+    while hold <= half_time {
+        let remaining = time
+            .checked_sub(&hold)
+            .expect("hold is at most half the time");
+        if &hold * &remaining > *distance {
+            break;
+        }
+        hold = &hold + &one;
+    }
+    if hold > half_time {
+        return BigUint::zero();
+    }
+    let twice_hold = hold << 1;
+    &time
+        .checked_sub(&twice_hold)
+        .expect("hold is at most half the time")
+        + &one
 }
 
 #[cfg(test)]
 mod tests {
+    mod examples {
+        use crate::day_6::{solve_part_1, solve_part_2};
+
+        #[test]
+        fn example_for_part_1() {
+            use std::fs;
+
+            let example_input = match fs::read_to_string("src/day_6/test_input_part_1.txt") {
+                Ok(result) => result,
+                Err(err) => panic!("Error in file reading: {err}"),
+            };
+
+            let answer = match solve_part_1(&example_input) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in solving part 1 example: {err}"),
+            };
+
+            assert_eq!(answer, 288)
+        }
+
+        #[test]
+        fn example_for_part_2() {
+            use std::fs;
+
+            let example_input = match fs::read_to_string("src/day_6/test_input_part_2.txt") {
+                Ok(result) => result,
+                Err(err) => panic!("Error in file reading: {err}"),
+            };
+
+            let answer = match solve_part_2(&example_input) {
+                Ok(result) => result,
+                Err(err) => panic!("Error in solving part 2 example: {err}"),
+            };
+
+            assert_eq!(answer, 71503)
+        }
+    }
+
     use crate::day_6::solutions_to_race_info;
 
     use super::RaceInfo;
@@ -88,11 +272,136 @@ mod tests {
             distance_to_beat: 9,
         };
 
-        let output = match solutions_to_race_info(&info) {
+        let output = solutions_to_race_info(&info);
+
+        assert_eq!(output, 4)
+    }
+
+    #[test]
+    fn explore_shows_both_models() {
+        use crate::day_6::{explore_race, RaceModel};
+
+        let model = RaceModel::default();
+        let winning = explore_race(
+            &model,
+            "race 1",
+            &RaceInfo {
+                time: 30,
+                distance_to_beat: 200,
+            },
+        );
+        let losing = explore_race(
+            &model,
+            "race 2",
+            &RaceInfo {
+                time: 10,
+                distance_to_beat: 25,
+            },
+        );
+
+        assert!(winning.starts_with(
+            "race 1 (30 ms, record 200 mm): exact holds 11..=19 win (9 ways), numeric 10.000..20.000 wins continuously;"
+        ));
+        assert!(losing.contains("exact no hold wins, numeric nothing wins continuously;"));
+        assert!(!winning.contains("MISMATCH") && !losing.contains("MISMATCH"));
+    }
+
+    #[test]
+    fn part_1_product_can_exceed_a_usize() {
+        use crate::day_6::solve_part_1;
+
+        let input = "Time:      1000000000001 1000000000001\nDistance:  0 0\n";
+
+        let answer = match solve_part_1(input) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in solving part 1: {err}"),
+        };
+
+        assert_eq!(answer.to_string(), "1000000000000000000000000")
+    }
+
+    #[test]
+    fn part_2_solves_a_race_beyond_128_bits() {
+        use crate::day_6::solve_part_2;
+
+        // the kerned race is 2 * 10^40 ms long, with a record of 10^80 - 1 mm, above u128::MAX
+        let input = format!(
+            "Time:      2{} 0\nDistance:  {} 9\n",
+            "0".repeat(39),
+            "9".repeat(79)
+        );
+
+        let answer = match solve_part_2(&input) {
             Ok(result) => result,
-            Err(e) => panic!("Error in solutions_to_race_info: {}", e),
+            Err(err) => panic!("Error in solving part 2: {err}"),
         };
 
-        assert_eq!(output, 4)
+        assert_eq!(answer, 1)
+    }
+
+    mod count_winning_holds {
+        use crate::day_6::{count_winning_holds, count_winning_holds_big};
+        use crate::shared::BigUint;
+
+        #[test]
+        fn excludes_holds_that_tie_the_record() {
+            // the roots are exactly 10 and 20, which only tie
+            assert_eq!(count_winning_holds(30, 200), 9);
+            assert_eq!(count_winning_holds(15, 40), 8);
+            assert_eq!(count_winning_holds(71530, 940200), 71503);
+            assert_eq!(count_winning_holds(10, 25), 0);
+        }
+
+        #[test]
+        fn matches_brute_force() {
+            for time in 0..60u128 {
+                for distance in 0..400u128 {
+                    let expected = (0..=time)
+                        .filter(|hold| hold * (time - hold) > distance)
+                        .count() as u128;
+                    assert_eq!(
+                        count_winning_holds(time, distance),
+                        expected,
+                        "time {time}, distance {distance}"
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn falls_back_when_128_bits_overflow() {
+            // time^2 overflows, so these go through BigUint
+            let half = (1u128 << 63) + 1;
+            let time = 2 * half;
+            assert_eq!(count_winning_holds(time, half * half), 0);
+            assert_eq!(count_winning_holds(time, half * half - 1), 1);
+            assert_eq!(count_winning_holds(time, half * half - 4), 3);
+            assert_eq!(count_winning_holds(u128::MAX, 0), u128::MAX - 1);
+        }
+
+        #[test]
+        fn solves_races_beyond_128_bits() {
+            let parse = |input: &str| match input.parse::<BigUint>() {
+                Ok(result) => result,
+                Err(err) => panic!("Error parsing `{input}`: {err}"),
+            };
+            let time = parse(&format!("2{}", "0".repeat(40)));
+            let half_squared = parse(&format!("1{}", "0".repeat(80)));
+            let one = BigUint::from(1);
+
+            assert_eq!(
+                count_winning_holds_big(&time, &half_squared),
+                BigUint::zero()
+            );
+            let just_below = match half_squared.checked_sub(&one) {
+                Some(result) => result,
+                None => panic!("Error subtracting"),
+            };
+            assert_eq!(count_winning_holds_big(&time, &just_below), one);
+            assert_eq!(
+                count_winning_holds_big(&time, &BigUint::zero()).to_string(),
+                format!("1{}", "9".repeat(40))
+            );
+        }
     }
 }
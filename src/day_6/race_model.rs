@@ -0,0 +1,320 @@
+use crate::day_6::RaceInfo;
+use std::ops::RangeInclusive;
+
+/// the rules for how holding the button turns into distance.
+///
+/// the boat gains `acceleration` mm/ms of speed for every millisecond held after the first `charge_delay`,
+/// up to `max_speed` if there is one. the puzzle's own rules are the `Default`: 1 mm/ms per ms, with no cap or delay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaceModel {
+    pub acceleration: u128,
+    pub max_speed: Option<u128>,
+    pub charge_delay: u128,
+}
+
+/// the exact result over whole-millisecond hold times
+#[derive(Debug, PartialEq)]
+pub struct RaceOutcome {
+    /// every hold time that beats the record, which is always one unbroken interval
+    pub winning_holds: Option<RangeInclusive<u128>>,
+    /// the shortest hold time that goes furthest
+    pub optimal_hold: u128,
+    pub optimal_distance: u128,
+    /// how far the optimal hold beats the record by, if it does
+    pub margin: Option<u128>,
+}
+
+/// the same result with the hold time treated as continuous, found with floating point roots
+#[derive(Debug, PartialEq)]
+pub struct NumericOutcome {
+    /// the open interval of hold times that beat the record
+    pub winning_holds: Option<(f64, f64)>,
+    pub optimal_hold: f64,
+    pub optimal_distance: f64,
+    pub margin: f64,
+}
+
+impl Default for RaceModel {
+    fn default() -> Self {
+        Self {
+            acceleration: 1,
+            max_speed: None,
+            charge_delay: 0,
+        }
+    }
+}
+
+impl RaceModel {
+    /// the speed the boat leaves with after holding the button for `hold` ms
+    pub fn speed(&self, hold: u128) -> u128 {
+        let speed = hold
+            .saturating_sub(self.charge_delay)
+            .saturating_mul(self.acceleration);
+        match self.max_speed {
+            Some(max_speed) => speed.min(max_speed),
+            None => speed,
+        }
+    }
+
+    /// the distance travelled in a race of `time` ms after holding for `hold` ms.
+    /// saturates at `u128::MAX`, which keeps comparisons against any smaller record exact
+    pub fn distance(&self, time: u128, hold: u128) -> u128 {
+        self.speed(hold).saturating_mul(time.saturating_sub(hold))
+    }
+
+    /// the first hold time at which the speed stops growing, if it ever does
+    pub fn capped_from(&self) -> Option<u128> {
+        let max_speed = self.max_speed?;
+        if self.acceleration == 0 {
+            return Some(self.charge_delay);
+        }
+        Some(
+            self.charge_delay
+                .saturating_add(max_speed.div_ceil(self.acceleration)),
+        )
+    }
+
+    /// solves the race exactly over whole-millisecond hold times.
+    ///
+    /// the distance rises to a single peak and falls again, so the peak is found from the vertex of the
+    /// uncapped quadratic (or where the speed caps, if sooner), and each edge of the winning interval is then
+    /// binary searched on its side of the peak
+    pub fn solve(&self, race_info: &RaceInfo) -> RaceOutcome {
+        let time = race_info.time;
+        let record = race_info.distance_to_beat;
+
+        // distance is acceleration * (hold - delay) * (time - hold) until capped, peaking at (time + delay) / 2
+        let vertex = (time / 2) + (self.charge_delay / 2) + (time % 2 + self.charge_delay % 2) / 2;
+        let peak_limit = self.capped_from().unwrap_or(u128::MAX).min(time);
+        // when the cap is not a multiple of the acceleration, the hold just before it can still go further
+        let (optimal_distance, peak) = [
+            vertex,
+            vertex.saturating_add(1),
+            peak_limit.saturating_sub(1),
+        ]
+        .into_iter()
+        .map(|hold| hold.min(peak_limit))
+        .map(|hold| (self.distance(time, hold), hold))
+        .max()
+        .unwrap_or((0, 0));
+        // the peak can be a short plateau, so look back for where it starts
+        let optimal_hold =
+            partition_point(0, peak, |hold| self.distance(time, hold) < optimal_distance);
+
+        let winning_holds = match optimal_distance > record {
+            true => {
+                // the first hold in 0..=optimal_hold that wins, and the last in optimal_hold..=time
+                let first =
+                    partition_point(0, optimal_hold, |hold| self.distance(time, hold) <= record);
+                let last = partition_point(optimal_hold, time, |hold| {
+                    self.distance(time, hold) > record
+                }) - 1;
+                Some(first..=last)
+            }
+            false => None,
+        };
+
+        RaceOutcome {
+            winning_holds,
+            optimal_hold,
+            optimal_distance,
+            margin: optimal_distance
+                .checked_sub(record)
+                .filter(|margin| *margin > 0),
+        }
+    }
+
+    /// solves the race with continuous hold times, using the quadratic formula on the uncapped part of the
+    /// distance curve and a straight line once the speed is capped
+    pub fn solve_numeric(&self, race_info: &RaceInfo) -> NumericOutcome {
+        let time = race_info.time as f64;
+        let record = race_info.distance_to_beat as f64;
+        let acceleration = self.acceleration as f64;
+        let delay = self.charge_delay as f64;
+        let distance = |hold: f64| {
+            let speed = (acceleration * (hold - delay).max(0.0))
+                .min(self.max_speed.map_or(f64::INFINITY, |max| max as f64));
+            speed * (time - hold).max(0.0)
+        };
+
+        let capped_from = match (self.max_speed, acceleration > 0.0) {
+            (Some(max_speed), true) => delay + max_speed as f64 / acceleration,
+            (Some(_), false) => delay,
+            (None, _) => f64::INFINITY,
+        }
+        .min(time);
+        let optimal_hold = ((time + delay) / 2.0).min(capped_from).clamp(0.0, time);
+        let optimal_distance = distance(optimal_hold);
+
+        let winning_holds = match optimal_distance > record && acceleration > 0.0 {
+            true => {
+                // roots of acceleration * (h - delay) * (time - h) = record
+                let discriminant = (time - delay).powi(2) - 4.0 * record / acceleration;
+                let lower = ((time + delay) - discriminant.max(0.0).sqrt()) / 2.0;
+                let upper = match distance(capped_from) > record && capped_from < time {
+                    // still winning when the speed caps, so the end is on the falling straight line
+                    true => time - record / self.max_speed.map_or(f64::INFINITY, |max| max as f64),
+                    false => ((time + delay) + discriminant.max(0.0).sqrt()) / 2.0,
+                };
+                Some((lower, upper))
+            }
+            false => None,
+        };
+
+        NumericOutcome {
+            winning_holds,
+            optimal_hold,
+            optimal_distance,
+            margin: optimal_distance - record,
+        }
+    }
+}
+
+impl NumericOutcome {
+    /// the whole-millisecond holds strictly inside `winning_holds`, which should match the exact model's
+    pub fn whole_holds(&self) -> Option<RangeInclusive<u128>> {
+        let (lower, upper) = self.winning_holds?;
+        let first = (lower.floor() + 1.0).max(0.0);
+        let last = upper.ceil() - 1.0;
+        match first <= last {
+            true => Some(first as u128..=last as u128),
+            false => None,
+        }
+    }
+}
+
+/// helper function to find the first value in `start..=end` for which `predicate` is false,
+/// given it is true for some prefix of the range and false after; returns `end + 1` if it is always true
+fn partition_point(start: u128, end: u128, predicate: impl Fn(u128) -> bool) -> u128 {
+    let (mut low, mut high) = (start, end.saturating_add(1));
+    while low < high {
+        let middle = low + (high - low) / 2;
+        match predicate(middle) {
+            true => low = middle + 1,
+            false => high = middle,
+        }
+    }
+    low
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RaceModel;
+    use crate::day_6::{count_winning_holds, RaceInfo};
+
+    fn brute_force(model: &RaceModel, race_info: &RaceInfo) -> Vec<u128> {
+        (0..=race_info.time)
+            .filter(|hold| model.distance(race_info.time, *hold) > race_info.distance_to_beat)
+            .collect()
+    }
+
+    #[test]
+    fn default_model_matches_the_puzzle() {
+        let model = RaceModel::default();
+        for time in 0..40 {
+            for distance_to_beat in 0..300 {
+                let race_info = RaceInfo {
+                    time,
+                    distance_to_beat,
+                };
+                let count = match model.solve(&race_info).winning_holds {
+                    Some(holds) => holds.end() - holds.start() + 1,
+                    None => 0,
+                };
+                assert_eq!(count, count_winning_holds(time, distance_to_beat));
+            }
+        }
+    }
+
+    #[test]
+    fn exact_solution_matches_brute_force_for_variants() {
+        for acceleration in 0..4 {
+            for max_speed in [None, Some(0), Some(3), Some(10)] {
+                for charge_delay in [0, 1, 5] {
+                    let model = RaceModel {
+                        acceleration,
+                        max_speed,
+                        charge_delay,
+                    };
+                    for time in 0..25 {
+                        for distance_to_beat in (0..120).step_by(7) {
+                            let race_info = RaceInfo {
+                                time,
+                                distance_to_beat,
+                            };
+                            let expected = brute_force(&model, &race_info);
+                            let outcome = model.solve(&race_info);
+
+                            let found: Vec<u128> =
+                                outcome.winning_holds.into_iter().flatten().collect();
+                            assert_eq!(found, expected, "{model:?} {race_info:?}");
+                            let best = (0..=time).max_by_key(|hold| {
+                                (model.distance(time, *hold), std::cmp::Reverse(*hold))
+                            });
+                            assert_eq!(Some(outcome.optimal_hold), best);
+                            assert_eq!(
+                                outcome.optimal_distance,
+                                model.distance(time, outcome.optimal_hold)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reports_the_optimal_hold_and_margin() {
+        let model = RaceModel {
+            acceleration: 2,
+            max_speed: Some(10),
+            charge_delay: 1,
+        };
+        let race_info = RaceInfo {
+            time: 20,
+            distance_to_beat: 100,
+        };
+
+        let outcome = model.solve(&race_info);
+
+        // capped at speed 10 after holding for 6 ms, then 14 ms left to travel
+        assert_eq!(outcome.optimal_hold, 6);
+        assert_eq!(outcome.optimal_distance, 140);
+        assert_eq!(outcome.margin, Some(40));
+        assert_eq!(outcome.winning_holds, Some(5..=9));
+    }
+
+    #[test]
+    fn numeric_solution_brackets_the_exact_one() {
+        for model in [
+            RaceModel::default(),
+            RaceModel {
+                acceleration: 2,
+                max_speed: Some(10),
+                charge_delay: 1,
+            },
+        ] {
+            let race_info = RaceInfo {
+                time: 30,
+                distance_to_beat: 200,
+            };
+
+            let exact = match model.solve(&race_info).winning_holds {
+                Some(holds) => holds,
+                None => panic!("expected {model:?} to win"),
+            };
+            let (lower, upper) = match model.solve_numeric(&race_info).winning_holds {
+                Some(holds) => holds,
+                None => panic!("expected {model:?} to win numerically"),
+            };
+
+            // the whole-millisecond holds are exactly the integers strictly inside the real interval
+            assert_eq!(lower.floor() as u128 + 1, *exact.start());
+            assert_eq!(upper.ceil() as u128 - 1, *exact.end());
+            assert_eq!(
+                model.solve_numeric(&race_info).whole_holds(),
+                Some(exact.clone())
+            );
+        }
+    }
+}
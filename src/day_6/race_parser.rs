@@ -0,0 +1,197 @@
+use crate::day_6::{BigRaceInfo, Race, RaceInfo};
+use crate::shared::{parse_number, BigUint, Location, NumberError};
+use thiserror::Error;
+
+/// the races on a `Time:` / `Distance:` sheet, read both as separate columns and as one kerned race
+#[derive(Debug, PartialEq)]
+pub struct RaceSheet {
+    pub races: Vec<Race>,
+    pub kerned_race: Race,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum RaceSheetError {
+    #[error("Missing `{0}` line")]
+    MissingLine(&'static str),
+
+    #[error("Unexpected line: `{0}`")]
+    UnexpectedLine(String),
+
+    #[error("{times} time column(s) but {distances} distance column(s)")]
+    MismatchedColumns { times: usize, distances: usize },
+
+    #[error("No races on the sheet")]
+    NoRaces,
+
+    #[error(transparent)]
+    Number(#[from] NumberError),
+}
+
+pub fn parse_race_sheet(input: &str) -> Result<RaceSheet, RaceSheetError> {
+    let mut times: Option<Vec<&str>> = None;
+    let mut distances: Option<Vec<&str>> = None;
+    for line in input.lines().filter(|line| !line.trim().is_empty()) {
+        if let Some(columns) = line.strip_prefix("Time:") {
+            times = Some(columns.split_whitespace().collect());
+        } else if let Some(columns) = line.strip_prefix("Distance:") {
+            distances = Some(columns.split_whitespace().collect());
+        } else {
+            return Err(RaceSheetError::UnexpectedLine(line.to_string()));
+        }
+    }
+    let times = times.ok_or(RaceSheetError::MissingLine("Time:"))?;
+    let distances = distances.ok_or(RaceSheetError::MissingLine("Distance:"))?;
+
+    if times.len() != distances.len() {
+        return Err(RaceSheetError::MismatchedColumns {
+            times: times.len(),
+            distances: distances.len(),
+        });
+    }
+    if times.is_empty() {
+        return Err(RaceSheetError::NoRaces);
+    }
+
+    let races = times
+        .iter()
+        .zip(distances.iter())
+        .enumerate()
+        .map(|(index, (time, distance))| parse_race(time, distance, &format!("race {}", index + 1)))
+        .collect::<Result<Vec<Race>, RaceSheetError>>()?;
+    // the kerning was a misprint: the columns are really the digits of one race
+    let kerned_race = parse_race(&times.concat(), &distances.concat(), "the kerned race")?;
+
+    Ok(RaceSheet { races, kerned_race })
+}
+
+/// helper function to read one race, in 128 bits if both its time and distance fit and as `BigUint`s if not
+fn parse_race(time: &str, distance: &str, name: &str) -> Result<Race, RaceSheetError> {
+    let time_location = Location::Named(format!("the time of {}", name));
+    let distance_location = Location::Named(format!("the distance of {}", name));
+    match (
+        parse_number::<u128>(time, time_location.clone()),
+        parse_number::<u128>(distance, distance_location.clone()),
+    ) {
+        (Ok(time), Ok(distance_to_beat)) => Ok(Race::Small(RaceInfo {
+            time,
+            distance_to_beat,
+        })),
+        (Err(err @ NumberError::Invalid { .. }), _)
+        | (_, Err(err @ NumberError::Invalid { .. })) => Err(err.into()),
+        _ => Ok(Race::Big(BigRaceInfo {
+            time: parse_big_number(time, time_location)?,
+            distance_to_beat: parse_big_number(distance, distance_location)?,
+        })),
+    }
+}
+
+/// helper function to read a number too large for a `u128`
+fn parse_big_number(text: &str, location: Location) -> Result<BigUint, NumberError> {
+    text.parse::<BigUint>().map_err(|_| NumberError::Invalid {
+        text: text.to_string(),
+        location,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_race_sheet, RaceSheetError};
+    use crate::day_6::{BigRaceInfo, Race, RaceInfo};
+    use crate::shared::{BigUint, Location, NumberError};
+
+    #[test]
+    fn parses_the_example_sheet() {
+        use std::fs;
+
+        let example_input = match fs::read_to_string("src/day_6/test_input_part_1.txt") {
+            Ok(result) => result,
+            Err(err) => panic!("Error in file reading: {err}"),
+        };
+
+        let race_sheet = match parse_race_sheet(&example_input) {
+            Ok(result) => result,
+            Err(err) => panic!("Error in parse_race_sheet: {err}"),
+        };
+
+        assert_eq!(
+            race_sheet.races,
+            vec![
+                Race::Small(RaceInfo {
+                    time: 7,
+                    distance_to_beat: 9
+                }),
+                Race::Small(RaceInfo {
+                    time: 15,
+                    distance_to_beat: 40
+                }),
+                Race::Small(RaceInfo {
+                    time: 30,
+                    distance_to_beat: 200
+                }),
+            ]
+        );
+        assert_eq!(
+            race_sheet.kerned_race,
+            Race::Small(RaceInfo {
+                time: 71530,
+                distance_to_beat: 940200
+            })
+        );
+    }
+
+    #[test]
+    fn reads_races_beyond_128_bits_as_big_numbers() {
+        let distance = format!("1{}", "0".repeat(40));
+        let race_sheet = match parse_race_sheet(&format!("Time: 7 1234\nDistance: 9 {distance}\n"))
+        {
+            Ok(result) => result,
+            Err(err) => panic!("Error in parse_race_sheet: {err}"),
+        };
+
+        assert_eq!(
+            race_sheet.races[1],
+            Race::Big(BigRaceInfo {
+                time: BigUint::from(1234),
+                distance_to_beat: distance.parse().unwrap()
+            })
+        );
+        assert!(matches!(race_sheet.kerned_race, Race::Big(_)));
+        assert_eq!(
+            parse_race_sheet(&format!("Time: 7x\nDistance: {distance}\n")),
+            Err(RaceSheetError::Number(NumberError::Invalid {
+                text: String::from("7x"),
+                location: Location::Named(String::from("the time of race 1"))
+            }))
+        );
+    }
+
+    #[test]
+    fn reports_mismatched_columns() {
+        assert_eq!(
+            parse_race_sheet("Time:      7  15   30\nDistance:  9  40\n"),
+            Err(RaceSheetError::MismatchedColumns {
+                times: 3,
+                distances: 2
+            })
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_unexpected_lines() {
+        assert_eq!(
+            parse_race_sheet("Time:      7  15   30\n"),
+            Err(RaceSheetError::MissingLine("Distance:"))
+        );
+        assert_eq!(
+            parse_race_sheet("Time: 7\nSpeed: 2\nDistance: 9\n"),
+            Err(RaceSheetError::UnexpectedLine(String::from("Speed: 2")))
+        );
+        assert_eq!(
+            parse_race_sheet("Time: 7x\nDistance: 9\n"),
+            Err(RaceSheetError::Number(NumberError::Invalid {
+                text: String::from("7x"),
+                location: Location::Named(String::from("the time of race 1"))
+            }))
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::io;
+use std::{collections::HashMap, env, fmt, fs, io, path::Path, str::FromStr};
 
 mod shared;
 mod day_1;
@@ -8,16 +8,363 @@ mod day_4;
 mod day_5;
 mod day_6;
 
+/// command line arguments: an optional day number followed by any `--flag` or `--flag=value` options
+struct RunnerArgs {
+    day: Option<usize>,
+    flags: HashMap<String, Option<String>>,
+}
+
+impl RunnerArgs {
+    fn from_args(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut day = None;
+        let mut flags = HashMap::new();
+        for arg in args {
+            match arg.strip_prefix("--") {
+                Some(flag) => match flag.split_once('=') {
+                    Some((name, value)) => flags.insert(name.to_string(), Some(value.to_string())),
+                    None => flags.insert(flag.to_string(), None),
+                },
+                None => {
+                    day = Some(arg.parse::<usize>().map_err(|err| err.to_string())?);
+                    None
+                }
+            };
+        }
+        Ok(Self { day, flags })
+    }
+
+    fn flag(&self, name: &str) -> Option<&Option<String>> {
+        self.flags.get(name)
+    }
+
+    /// parses the value of `--name=value`, if the flag was given
+    fn parsed_flag<T: FromStr>(&self, name: &str) -> Result<Option<T>, String>
+    where
+        T::Err: fmt::Display,
+    {
+        match self.flag(name) {
+            Some(Some(value)) => value
+                .parse::<T>()
+                .map(Some)
+                .map_err(|err| format!("Invalid --{} value `{}`: {}", name, value, err)),
+            Some(None) => Err(format!("--{} needs a value, e.g. --{}=2", name, name)),
+            None => Ok(None),
+        }
+    }
+}
+
 fn main() -> Result<(), String> {
-    println!("Enter day number: ");
+    let args = RunnerArgs::from_args(env::args().skip(1))?;
+
+    let day: usize = match args.day {
+        Some(day) => day,
+        None => {
+            println!("Enter day number: ");
+
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .map_err(|err| err.to_string())?;
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).map_err(|err| err.to_string())?;
+            input
+                .trim()
+                .parse::<usize>()
+                .map_err(|err| err.to_string())?
+        }
+    };
 
-    let day: usize = input.trim().parse::<usize>().map_err(|err| err.to_string())?;
+    check_day_flags(day, &args).map_err(|err| format!("Error in day {} options: {}", day, err))?;
 
     run_day_solution(day);
 
+    run_day_flags(day, &args).map_err(|err| format!("Error in day {} options: {}", day, err))
+}
+
+/// checks that every flag is an option for `day` and that any value it was given parses, before anything is solved
+fn check_day_flags(day: usize, args: &RunnerArgs) -> Result<(), String> {
+    for (name, value) in args.flags.iter() {
+        let needs_value = || match value {
+            Some(_) => Ok(()),
+            None => Err(format!("--{} needs a value", name)),
+        };
+        match (day, name.as_str()) {
+            (2, "strict" | "report")
+            | (3, "report" | "render" | "legend" | "ruler" | "stream")
+            | (4, "trace" | "validate")
+            | (5, "witness" | "validate" | "check" | "print-map")
+            | (6, "chart") => {}
+            (2, "limits")
+            | (3, "blanks" | "symbols")
+            | (5, "trace" | "almanac" | "normalise")
+            | (6, "svg") => needs_value()?,
+            (2, "top") | (3, "gear-parts") => {
+                args.parsed_flag::<usize>(name)?;
+            }
+            (2 | 3 | 5, "bench") => {
+                if let Some(count) = value {
+                    count
+                        .parse::<usize>()
+                        .map_err(|err| format!("Invalid --bench value `{}`: {}", count, err))?;
+                }
+            }
+            (3, "gear") => {
+                args.parsed_flag::<char>(name)?;
+            }
+            (3, "aggregate") => {
+                args.parsed_flag::<day_3::Aggregation>(name)?;
+            }
+            (3, "rows" | "columns") => {
+                needs_value()?;
+                day_3::parse_window(value.as_deref().unwrap_or_default())?;
+            }
+            (4, "past-end") => {
+                args.parsed_flag::<day_4::PastTheEnd>(name)?;
+            }
+            (6, "acceleration" | "max-speed" | "charge-delay") => {
+                args.parsed_flag::<u128>(name)?;
+            }
+            _ => return Err(format!("--{} is not an option for day {}", name, day)),
+        }
+    }
+    Ok(())
+}
+
+/// runs any extra day-specific modes requested on the command line
+fn run_day_flags(day: usize, args: &RunnerArgs) -> Result<(), String> {
+    if day == 2 && !args.flags.is_empty() {
+        let strictness = match args.flag("strict") {
+            Some(_) => day_2::Strictness::Strict,
+            None => day_2::Strictness::Lenient,
+        };
+        let rules = match args.flag("limits") {
+            Some(limits) => day_2::BagRules::parse_limits(
+                limits
+                    .as_deref()
+                    .ok_or("--limits needs a bag, e.g. --limits=\"12 red, 13 green, 14 blue\"")?,
+                strictness,
+            )?,
+            None => day_2::BagRules {
+                strictness,
+                ..day_2::BagRules::default()
+            },
+        };
+        if args.flag("limits").is_some() || args.flag("strict").is_some() {
+            let result = day_2::solve_with(&rules)?;
+            println!(
+                "With this bag:\nPart 1: {}\nPart 2: {}",
+                result.part_1, result.part_2
+            );
+        }
+        if let Some(format) = args.flag("report") {
+            let report = day_2::analyse(&rules, args.parsed_flag("top")?.unwrap_or(10))?;
+            match format.as_deref() {
+                None | Some("table") => print!("{}", report),
+                Some("json") => print!("{}", report.to_json()),
+                Some(other) => {
+                    return Err(format!(
+                        "Unknown report format `{}`, expected table or json",
+                        other
+                    ))
+                }
+            }
+        }
+        if let Some(games) = args.flag("bench") {
+            let games = match games {
+                Some(games) => games
+                    .parse::<usize>()
+                    .map_err(|err| format!("Invalid --bench game count: {}", err))?,
+                // the regex parser takes about a millisecond a game
+                None => 10_000,
+            };
+            println!("{}", day_2::benchmark_parsers(games)?);
+        }
+    }
+
+    if day == 3 && !args.flags.is_empty() {
+        let defaults = day_3::SchematicRules::default();
+        let characters = |name: &str| match args.flag(name) {
+            Some(Some(characters)) => Ok(Some(characters.chars().collect::<Vec<char>>())),
+            Some(None) => Err(format!(
+                "--{} needs some characters, e.g. --{}=\".\"",
+                name, name
+            )),
+            None => Ok(None),
+        };
+        let gear_symbol = match args.parsed_flag::<char>("gear")? {
+            Some(gear_symbol) => gear_symbol,
+            None => defaults.gear_symbol,
+        };
+        let rules = day_3::SchematicRules {
+            blanks: characters("blanks")?.unwrap_or(defaults.blanks),
+            symbols: match characters("symbols")? {
+                Some(symbols) => day_3::SymbolSet::Only(symbols),
+                None => day_3::SymbolSet::AnyOther,
+            },
+            gear_symbol,
+            gear_parts: args
+                .parsed_flag("gear-parts")?
+                .unwrap_or(defaults.gear_parts),
+            aggregation: args
+                .parsed_flag("aggregate")?
+                .unwrap_or(defaults.aggregation),
+        };
+        let rule_flags = ["blanks", "symbols", "gear", "gear-parts", "aggregate"];
+        if args
+            .flags
+            .keys()
+            .any(|name| rule_flags.contains(&name.as_str()))
+        {
+            let result = day_3::solve_with(&rules)?;
+            println!(
+                "With these rules:\nPart 1: {}\nPart 2: {}",
+                result.part_1, result.part_2
+            );
+        }
+        if args.flag("report").is_some() {
+            print!("{}", day_3::symbol_report(&rules)?);
+        }
+        if let Some(path) = args.flag("stream") {
+            let path = path.as_deref().unwrap_or("src/day_3/input.txt");
+            let result = day_3::solve_file_streaming(path, &rules)?;
+            println!(
+                "Streamed from {}:\nPart 1: {}\nPart 2: {}",
+                path, result.part_1, result.part_2
+            );
+        }
+        if let Some(copies) = args.flag("bench") {
+            let copies = match copies {
+                Some(copies) => copies
+                    .parse::<usize>()
+                    .map_err(|err| format!("Invalid --bench copy count: {}", err))?,
+                None => 100,
+            };
+            println!("{}", day_3::benchmark_part_1(copies)?);
+        }
+        if args.flag("render").is_some() {
+            let window = |name: &str| match args.flag(name) {
+                Some(Some(window)) => day_3::parse_window(window).map(Some),
+                Some(None) => Err(format!("--{} needs a window, e.g. --{}=10..20", name, name)),
+                None => Ok(None),
+            };
+            let options = day_3::RenderOptions {
+                rows: window("rows")?,
+                columns: window("columns")?,
+                legend: args.flag("legend").is_some(),
+                ruler: args.flag("ruler").is_some(),
+            };
+            print!("{}", day_3::render_schematic(&rules, &options)?);
+        }
+    }
+
+    if day == 4 {
+        let past_the_end = args.parsed_flag("past-end")?.unwrap_or_default();
+        if args.flag("past-end").is_some() {
+            let result = day_4::solve_with(past_the_end)?;
+            println!(
+                "With copies past the final card {}:\nPart 1: {}\nPart 2: {}",
+                match past_the_end {
+                    day_4::PastTheEnd::Clamp => "clamped",
+                    day_4::PastTheEnd::Report => "reported",
+                },
+                result.part_1,
+                result.part_2
+            );
+        }
+        if args.flag("validate").is_some() {
+            let issues = day_4::validate()?;
+            println!("{} issue(s) with the card ids", issues.len());
+            for issue in issues {
+                println!("  {}", issue);
+            }
+        }
+        if args.flag("trace").is_some() {
+            print!("{}", day_4::trace(past_the_end)?);
+        }
+    }
+
+    if day == 5 {
+        if let Some(seeds) = args.flag("trace") {
+            let seeds = seeds
+                .as_deref()
+                .ok_or("--trace needs a seed or seed range, e.g. --trace=79 or --trace=79..93")?;
+            print!("{}", day_5::explain(seeds)?);
+        }
+        if args.flag("witness").is_some() {
+            let (part_1, part_2) = day_5::find_witnesses()?;
+            println!("Part 1 witness: {}\nPart 2 witness: {}", part_1, part_2);
+        }
+        if args.flag("check").is_some() {
+            println!("{}", day_5::check_part_2()?);
+        }
+        let almanac = match args.flag("almanac") {
+            Some(path) => path.as_deref().ok_or("--almanac needs a file path")?,
+            None => "src/day_5/input.txt",
+        };
+        if args.flag("validate").is_some() {
+            let issues = day_5::validate_almanac(almanac)?;
+            println!("{}: {} issue(s)", almanac, issues.len());
+            for issue in issues {
+                println!("  {}", issue);
+            }
+        }
+        if args.flag("print-map").is_some() {
+            print!("{}", day_5::print_seed_to_location_map(almanac)?);
+        }
+        if let Some(output_path) = args.flag("normalise") {
+            let output_path = output_path
+                .as_deref()
+                .ok_or("--normalise needs an output file path, e.g. --normalise=normalised.txt")?;
+            day_5::normalise_almanac(almanac, output_path)?;
+            println!("Normalised {} into {}", almanac, output_path);
+        }
+        if let Some(count) = args.flag("bench") {
+            let count = match count {
+                Some(count) => count
+                    .parse::<usize>()
+                    .map_err(|err| format!("Invalid --bench point count: {}", err))?,
+                None => 1_000_000,
+            };
+            println!("{}", day_5::benchmark_map_points(count)?);
+        }
+    }
+
+    if day == 6 {
+        let default_model = day_6::RaceModel::default();
+        let model = day_6::RaceModel {
+            acceleration: args
+                .parsed_flag("acceleration")?
+                .unwrap_or(default_model.acceleration),
+            max_speed: args.parsed_flag("max-speed")?,
+            charge_delay: args
+                .parsed_flag("charge-delay")?
+                .unwrap_or(default_model.charge_delay),
+        };
+        if ["acceleration", "max-speed", "charge-delay"]
+            .iter()
+            .any(|name| args.flag(name).is_some())
+        {
+            print!("{}", day_6::explore(&model)?);
+        }
+        if args.flag("chart").is_some() {
+            for (name, race_info) in day_6::named_races()? {
+                println!("{}:", name);
+                print!("{}", day_6::render_ascii(&model, &race_info, 60, 16));
+            }
+        }
+        if let Some(directory) = args.flag("svg") {
+            let directory = directory
+                .as_deref()
+                .ok_or("--svg needs an output directory, e.g. --svg=charts")?;
+            fs::create_dir_all(directory).map_err(|err| err.to_string())?;
+            for (name, race_info) in day_6::named_races()? {
+                let path = Path::new(directory).join(format!("{}.svg", name.replace(' ', "_")));
+                fs::write(&path, day_6::render_svg(&model, &race_info, 640, 360))
+                    .map_err(|err| format!("Error writing {}: {}", path.display(), err))?;
+                println!("Wrote {}", path.display());
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,139 @@
+use std::{
+    fmt,
+    iter::{Product, Sum},
+    mem,
+    ops::{Add, AddAssign, Mul},
+};
+
+use super::BigUint;
+
+/// A puzzle answer. It stays a `usize` for as long as checked arithmetic allows,
+/// and switches to a `BigUint` the first time an addition or multiplication would overflow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Small(usize),
+    /// always too large for a `usize`
+    Big(BigUint),
+}
+
+impl Answer {
+    /// helper function to widen either kind of answer for arbitrary precision arithmetic
+    fn to_big(&self) -> BigUint {
+        match self {
+            Answer::Small(value) => BigUint::from(*value as u128),
+            Answer::Big(value) => value.clone(),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Small(value)
+    }
+}
+
+impl From<BigUint> for Answer {
+    fn from(value: BigUint) -> Self {
+        match value
+            .to_u128()
+            .and_then(|small| usize::try_from(small).ok())
+        {
+            Some(small) => Answer::Small(small),
+            None => Answer::Big(value),
+        }
+    }
+}
+
+impl Add for Answer {
+    type Output = Answer;
+
+    fn add(self, other: Answer) -> Answer {
+        if let (Answer::Small(a), Answer::Small(b)) = (&self, &other) {
+            if let Some(sum) = a.checked_add(*b) {
+                return Answer::Small(sum);
+            }
+        }
+        Answer::from(&self.to_big() + &other.to_big())
+    }
+}
+
+impl AddAssign for Answer {
+    fn add_assign(&mut self, other: Answer) {
+        *self = mem::replace(self, Answer::Small(0)) + other;
+    }
+}
+
+impl Mul for Answer {
+    type Output = Answer;
+
+    fn mul(self, other: Answer) -> Answer {
+        if let (Answer::Small(a), Answer::Small(b)) = (&self, &other) {
+            if let Some(product) = a.checked_mul(*b) {
+                return Answer::Small(product);
+            }
+        }
+        Answer::from(&self.to_big() * &other.to_big())
+    }
+}
+
+impl Sum for Answer {
+    fn sum<I: Iterator<Item = Answer>>(iter: I) -> Answer {
+        iter.fold(Answer::Small(0), |total, value| total + value)
+    }
+}
+
+impl Product for Answer {
+    fn product<I: Iterator<Item = Answer>>(iter: I) -> Answer {
+        iter.fold(Answer::Small(1), |total, value| total * value)
+    }
+}
+
+impl PartialEq<usize> for Answer {
+    fn eq(&self, other: &usize) -> bool {
+        *self == Answer::Small(*other)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Small(value) => fmt::Display::fmt(value, f),
+            Answer::Big(value) => fmt::Display::fmt(value, f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Answer;
+
+    #[test]
+    fn stays_small_until_it_overflows() {
+        let sum: Answer = [1, 2, 3].into_iter().map(Answer::from).sum();
+        assert_eq!(sum, Answer::Small(6));
+
+        let overflowed = Answer::from(usize::MAX) + Answer::from(2);
+        assert!(matches!(overflowed, Answer::Big(_)));
+        assert_eq!(overflowed.to_string(), (usize::MAX as u128 + 2).to_string());
+    }
+
+    #[test]
+    fn multiplies_past_a_usize() {
+        let product: Answer = [usize::MAX, usize::MAX, 2]
+            .into_iter()
+            .map(Answer::from)
+            .product();
+        let square = Answer::from(usize::MAX) * Answer::from(usize::MAX);
+        assert_eq!(product, square.clone() + square);
+        assert_eq!(
+            (Answer::from(1 << 32) * Answer::from(1 << 32) * Answer::from(1 << 32)).to_string(),
+            "79228162514264337593543950336"
+        );
+    }
+
+    #[test]
+    fn compares_with_plain_numbers() {
+        assert_eq!(Answer::from(42), 42);
+        assert_ne!(Answer::from(usize::MAX) * Answer::from(4), 0);
+    }
+}
@@ -0,0 +1,325 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Mul, Shl, Shr},
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// an arbitrary precision unsigned integer, for races too long to solve in 128 bits and answers too large for a `usize`.
+/// stored as little-endian base 2^32 limbs with no trailing zero limbs, so zero has no limbs at all
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BigUintParseError {
+    #[error("Cannot parse an empty string as a number")]
+    Empty,
+
+    #[error("Invalid digit `{0}`")]
+    InvalidDigit(char),
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// helper function to restore the no-trailing-zero-limbs invariant
+    fn normalised(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    /// the number of bits needed to write the number, 0 for zero
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() * 32 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0u128, |total, &limb| (total << 32) | limb as u128),
+        )
+    }
+
+    /// subtracts `other`, returning `None` if it is larger than `self`
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (index, &limb) in self.limbs.iter().enumerate() {
+            let mut difference =
+                limb as i64 - other.limbs.get(index).copied().unwrap_or(0) as i64 - borrow;
+            borrow = 0;
+            if difference < 0 {
+                difference += 1 << 32;
+                borrow = 1;
+            }
+            limbs.push(difference as u32);
+        }
+        Some(BigUint { limbs }.normalised())
+    }
+
+    /// helper function for parsing and printing: `self * factor + addend`
+    fn mul_add_small(&self, factor: u32, addend: u32) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = addend as u64;
+        for &limb in self.limbs.iter() {
+            let product = limb as u64 * factor as u64 + carry;
+            limbs.push(product as u32);
+            carry = product >> 32;
+        }
+        limbs.push(carry as u32);
+        BigUint { limbs }.normalised()
+    }
+
+    /// helper function for printing: returns `(self / divisor, self % divisor)`
+    fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+        let mut limbs = vec![0; self.limbs.len()];
+        let mut remainder = 0u64;
+        for (index, &limb) in self.limbs.iter().enumerate().rev() {
+            let current = (remainder << 32) | limb as u64;
+            limbs[index] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        (BigUint { limbs }.normalised(), remainder as u32)
+    }
+
+    /// the largest integer whose square is no more than `self`, found bit by bit so no division is needed
+    pub fn isqrt(&self) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let mut remainder = self.clone();
+        let mut root = BigUint::zero();
+        // the highest power of four no larger than self
+        let mut bit = BigUint::from(1u128) << ((self.bits() - 1) & !1);
+        while !bit.is_zero() {
+            let candidate = &root + &bit;
+            match remainder.checked_sub(&candidate) {
+                Some(smaller) => {
+                    remainder = smaller;
+                    root = &(&root >> 1) + &bit;
+                }
+                None => root = &root >> 1,
+            }
+            bit = &bit >> 2;
+        }
+        root
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(value: u128) -> Self {
+        let limbs = (0..4).map(|index| (value >> (32 * index)) as u32).collect();
+        BigUint { limbs }.normalised()
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = BigUintParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.is_empty() {
+            return Err(BigUintParseError::Empty);
+        }
+        input.chars().try_fold(BigUint::zero(), |total, character| {
+            match character.to_digit(10) {
+                Some(digit) => Ok(total.mul_add_small(10, digit)),
+                None => Err(BigUintParseError::InvalidDigit(character)),
+            }
+        })
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let length = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(length + 1);
+        let mut carry = 0u64;
+        for index in 0..length {
+            let sum = self.limbs.get(index).copied().unwrap_or(0) as u64
+                + other.limbs.get(index).copied().unwrap_or(0) as u64
+                + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+        BigUint { limbs }.normalised()
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let product = a as u64 * b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint { limbs }.normalised()
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        if self.is_zero() {
+            return self;
+        }
+        let (whole_limbs, bits) = (shift / 32, shift % 32);
+        let mut limbs = vec![0u32; whole_limbs];
+        let mut carry = 0u32;
+        for &limb in self.limbs.iter() {
+            limbs.push((limb << bits) | carry);
+            carry = if bits == 0 { 0 } else { limb >> (32 - bits) };
+        }
+        limbs.push(carry);
+        BigUint { limbs }.normalised()
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        let (whole_limbs, bits) = (shift / 32, shift % 32);
+        let kept = self.limbs.get(whole_limbs..).unwrap_or(&[]);
+        let limbs = (0..kept.len())
+            .map(|index| {
+                let high = match (bits, kept.get(index + 1)) {
+                    (0, _) | (_, None) => 0,
+                    (_, Some(&next)) => next << (32 - bits),
+                };
+                (kept[index] >> bits) | high
+            })
+            .collect();
+        BigUint { limbs }.normalised()
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // peel off nine decimal digits at a time, least significant first
+        let mut chunks: Vec<u32> = Vec::new();
+        let mut remaining = self.clone();
+        while !remaining.is_zero() {
+            let (quotient, chunk) = remaining.div_rem_small(1_000_000_000);
+            chunks.push(chunk);
+            remaining = quotient;
+        }
+        let mut chunks = chunks.iter().rev();
+        let mut digits = match chunks.next() {
+            Some(first) => first.to_string(),
+            None => String::from("0"),
+        };
+        for chunk in chunks {
+            digits.push_str(&format!("{chunk:09}"));
+        }
+        // so that widths and alignment work as they do for the primitive integers
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BigUint, BigUintParseError};
+
+    fn parse(input: &str) -> BigUint {
+        match input.parse::<BigUint>() {
+            Ok(result) => result,
+            Err(err) => panic!("Error parsing `{input}`: {err}"),
+        }
+    }
+
+    #[test]
+    fn round_trips_through_decimal() {
+        for input in [
+            "0",
+            "7",
+            "4294967296",
+            "123456789012345678901234567890123456789012",
+        ] {
+            assert_eq!(parse(input).to_string(), input);
+        }
+        assert_eq!(
+            "12a".parse::<BigUint>(),
+            Err(BigUintParseError::InvalidDigit('a'))
+        );
+    }
+
+    #[test]
+    fn arithmetic_matches_u128() {
+        let (a, b) = (0xdead_beef_cafe_f00d_u128, 0x1234_5678_9abc_u128);
+        let (big_a, big_b) = (BigUint::from(a), BigUint::from(b));
+
+        assert_eq!((&big_a + &big_b).to_u128(), Some(a + b));
+        assert_eq!((&big_a * &big_b).to_u128(), Some(a * b));
+        assert_eq!(
+            big_a.checked_sub(&big_b).and_then(|c| c.to_u128()),
+            Some(a - b)
+        );
+        assert_eq!(big_b.checked_sub(&big_a), None);
+        assert_eq!((big_a.clone() << 7).to_u128(), Some(a << 7));
+        assert_eq!((&big_a >> 33).to_u128(), Some(a >> 33));
+        assert!(big_b < big_a);
+    }
+
+    #[test]
+    fn isqrt_is_exact_around_perfect_squares() {
+        let root = parse("100000000000000000000000000000000000007");
+        let square = &root * &root;
+        let one = BigUint::from(1);
+
+        assert_eq!(square.isqrt(), root);
+        assert_eq!((&square + &one).isqrt(), root);
+        assert_eq!(
+            square.checked_sub(&one).map(|n| n.isqrt()),
+            root.checked_sub(&one)
+        );
+        assert_eq!(BigUint::from(15).isqrt(), BigUint::from(3));
+    }
+}
@@ -0,0 +1,336 @@
+use std::ops::{BitAnd, BitOr, BitOrAssign, Range, Shl, Shr};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// a fixed-length row of bits packed 64 to a word, with bit `i` for column `i`, so rows combine a word at a time
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitRow {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitRow {
+    /// a row of `len` unset bits
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(WORD_BITS)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn set(&mut self, index: usize) {
+        assert!(
+            index < self.len,
+            "bit {index} is outside a row of {}",
+            self.len
+        );
+        self.words[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        index < self.len && self.words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// whether any bit in `range` is set
+    pub fn any_in(&self, range: Range<usize>) -> bool {
+        let (start, end) = (range.start, range.end.min(self.len));
+        if start >= end {
+            return false;
+        }
+        let (first, last) = (start / WORD_BITS, (end - 1) / WORD_BITS);
+        (first..=last).any(|word_index| {
+            let mut mask = u64::MAX;
+            if word_index == first {
+                mask &= u64::MAX << (start % WORD_BITS);
+            }
+            if word_index == last {
+                mask &= u64::MAX >> (WORD_BITS - 1 - (end - 1) % WORD_BITS);
+            }
+            self.words[word_index] & mask != 0
+        })
+    }
+
+    /// the bits set here but not in `other`
+    pub fn and_not(&self, other: &BitRow) -> BitRow {
+        self.zip_words(other, |word, other| word & !other)
+    }
+
+    /// the index of every set bit, in order
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(word_index, word)| {
+                let mut word = *word;
+                std::iter::from_fn(move || match word {
+                    0 => None,
+                    _ => {
+                        let bit = word.trailing_zeros() as usize;
+                        word &= word - 1;
+                        Some(word_index * WORD_BITS + bit)
+                    }
+                })
+            })
+    }
+
+    /// every maximal run of consecutive set bits, in order
+    pub fn runs(&self) -> impl Iterator<Item = Range<usize>> {
+        // a run starts where the bit before is unset, and ends where the bit after is unset
+        let starts = self.and_not(&(self << 1));
+        let ends = self.and_not(&(self >> 1));
+        let starts: Vec<usize> = starts.ones().collect();
+        let ends: Vec<usize> = ends.ones().collect();
+        starts
+            .into_iter()
+            .zip(ends)
+            .map(|(start, last)| start..last + 1)
+    }
+
+    /// each bit along with its neighbours on either side
+    pub fn widened(&self) -> BitRow {
+        let mut output = self << 1;
+        output |= &(self >> 1);
+        output |= self;
+        output
+    }
+
+    /// helper function to combine two rows of the same length word by word
+    fn zip_words(&self, other: &BitRow, combine: impl Fn(u64, u64) -> u64) -> BitRow {
+        assert_eq!(self.len, other.len, "rows must be the same length");
+        BitRow {
+            words: self
+                .words
+                .iter()
+                .zip(other.words.iter())
+                .map(|(word, other)| combine(*word, *other))
+                .collect(),
+            len: self.len,
+        }
+    }
+
+    /// helper function to clear the unused bits past `len` in the last word
+    fn clear_tail(&mut self) {
+        let tail = self.len % WORD_BITS;
+        if let (Some(last), true) = (self.words.last_mut(), tail != 0) {
+            *last &= u64::MAX >> (WORD_BITS - tail);
+        }
+    }
+}
+
+impl BitOr for &BitRow {
+    type Output = BitRow;
+
+    fn bitor(self, other: &BitRow) -> BitRow {
+        self.zip_words(other, |word, other| word | other)
+    }
+}
+
+impl BitOrAssign<&BitRow> for BitRow {
+    fn bitor_assign(&mut self, other: &BitRow) {
+        assert_eq!(self.len, other.len, "rows must be the same length");
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other;
+        }
+    }
+}
+
+impl BitAnd for &BitRow {
+    type Output = BitRow;
+
+    fn bitand(self, other: &BitRow) -> BitRow {
+        self.zip_words(other, |word, other| word & other)
+    }
+}
+
+/// moves every bit `by` columns towards the end of the row, dropping any that fall off
+impl Shl<usize> for &BitRow {
+    type Output = BitRow;
+
+    fn shl(self, by: usize) -> BitRow {
+        let (word_shift, bit_shift) = (by / WORD_BITS, by % WORD_BITS);
+        let mut words = vec![0; self.words.len()];
+        for (source, word) in words.iter_mut().skip(word_shift).enumerate() {
+            *word = self.words[source] << bit_shift;
+            if bit_shift != 0 && source > 0 {
+                *word |= self.words[source - 1] >> (WORD_BITS - bit_shift);
+            }
+        }
+        let mut output = BitRow {
+            words,
+            len: self.len,
+        };
+        output.clear_tail();
+        output
+    }
+}
+
+/// moves every bit `by` columns towards the start of the row, dropping any that fall off
+impl Shr<usize> for &BitRow {
+    type Output = BitRow;
+
+    fn shr(self, by: usize) -> BitRow {
+        let (word_shift, bit_shift) = (by / WORD_BITS, by % WORD_BITS);
+        let mut words = vec![0; self.words.len()];
+        for (index, word) in words
+            .iter_mut()
+            .enumerate()
+            .take(self.words.len().saturating_sub(word_shift))
+        {
+            let source = index + word_shift;
+            *word = self.words[source] >> bit_shift;
+            if bit_shift != 0 && source + 1 < self.words.len() {
+                *word |= self.words[source + 1] << (WORD_BITS - bit_shift);
+            }
+        }
+        BitRow {
+            words,
+            len: self.len,
+        }
+    }
+}
+
+/// a grid of equally long `BitRow`s
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitGrid {
+    rows: Vec<BitRow>,
+}
+
+impl BitGrid {
+    /// a grid of `rows` by `columns` unset bits
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self {
+            rows: vec![BitRow::new(columns); rows],
+        }
+    }
+
+    pub fn row(&self, row: usize) -> &BitRow {
+        &self.rows[row]
+    }
+
+    pub fn set(&mut self, row: usize, column: usize) {
+        self.rows[row].set(column);
+    }
+
+    /// every bit along with its eight neighbours: each row is widened with shifts, then ORed with the rows
+    /// either side of it
+    pub fn dilated(&self) -> BitGrid {
+        let widened: Vec<BitRow> = self.rows.iter().map(|row| row.widened()).collect();
+        let rows = (0..widened.len())
+            .map(|index| {
+                let mut output = widened[index].clone();
+                if index > 0 {
+                    output |= &widened[index - 1];
+                }
+                if let Some(below) = widened.get(index + 1) {
+                    output |= below;
+                }
+                output
+            })
+            .collect();
+        BitGrid { rows }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BitGrid, BitRow};
+
+    fn row_from(pattern: &str) -> BitRow {
+        let mut row = BitRow::new(pattern.len());
+        for (index, character) in pattern.chars().enumerate() {
+            if character == '#' {
+                row.set(index);
+            }
+        }
+        row
+    }
+
+    fn pattern_of(row: &BitRow) -> String {
+        (0..row.len())
+            .map(|index| match row.get(index) {
+                true => '#',
+                false => '.',
+            })
+            .collect()
+    }
+
+    mod bit_row {
+        use super::{pattern_of, row_from, BitRow};
+
+        #[test]
+        fn shifts_across_words() {
+            let mut row = BitRow::new(130);
+            row.set(0);
+            row.set(63);
+            row.set(129);
+
+            let left = &row << 1;
+            assert_eq!(left.ones().collect::<Vec<_>>(), vec![1, 64]);
+            let right = &row >> 1;
+            assert_eq!(right.ones().collect::<Vec<_>>(), vec![62, 128]);
+            let far = &row << 70;
+            assert_eq!(far.ones().collect::<Vec<_>>(), vec![70]);
+            assert_eq!((&row >> 64).ones().collect::<Vec<_>>(), vec![65]);
+        }
+
+        #[test]
+        fn combines_rows() {
+            let first = row_from("##..#.");
+            let second = row_from(".#.##.");
+
+            assert_eq!(pattern_of(&(&first | &second)), "##.##.");
+            assert_eq!(pattern_of(&(&first & &second)), ".#..#.");
+            assert_eq!(pattern_of(&first.and_not(&second)), "#.....");
+            assert_eq!(pattern_of(&row_from("..#...#").widened()), ".###.##");
+            assert_eq!(first.count_ones(), 3);
+        }
+
+        #[test]
+        fn finds_runs_and_ranges() {
+            let mut row = BitRow::new(200);
+            for index in (60..70).chain([100]).chain(190..200) {
+                row.set(index);
+            }
+
+            assert_eq!(
+                row.runs().collect::<Vec<_>>(),
+                vec![60..70, 100..101, 190..200]
+            );
+            assert!(row.any_in(0..61));
+            assert!(!row.any_in(70..100));
+            assert!(row.any_in(199..300));
+            assert!(!row.any_in(150..150));
+        }
+    }
+
+    mod bit_grid {
+        use super::{pattern_of, BitGrid};
+
+        #[test]
+        fn dilates_in_every_direction() {
+            let mut grid = BitGrid::new(4, 5);
+            grid.set(1, 0);
+            grid.set(3, 4);
+
+            let dilated = grid.dilated();
+
+            let patterns: Vec<String> = (0..4).map(|row| pattern_of(dilated.row(row))).collect();
+            assert_eq!(patterns, vec!["##...", "##...", "##.##", "...##"]);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    fmt,
+    num::{IntErrorKind, ParseIntError},
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// Where in a puzzle input a number was read or combined, for error messages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Location {
+    /// counted from 1
+    Line(usize),
+    /// counted from 1
+    Cell { line: usize, column: usize },
+    /// anywhere else, described in words, e.g. `card 12`
+    Named(String),
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Location::Line(line) => write!(f, "line {}", line),
+            Location::Cell { line, column } => write!(f, "line {}, column {}", line, column),
+            Location::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    Adding,
+    Multiplying,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Adding => write!(f, "adding"),
+            Operation::Multiplying => write!(f, "multiplying"),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum NumberError {
+    #[error("Expected a number at {location}, found `{text}`")]
+    Invalid { text: String, location: Location },
+
+    #[error("Number `{text}` at {location} is too large")]
+    TooLarge { text: String, location: Location },
+
+    #[error("Overflow {operation} at {location}")]
+    Overflow {
+        operation: Operation,
+        location: Location,
+    },
+}
+
+/// The unsigned integer types the puzzles count with.
+pub trait CheckedNumber: Copy + FromStr<Err = ParseIntError> {
+    const ZERO: Self;
+    const TEN: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn from_digit(digit: u8) -> Self;
+}
+
+macro_rules! impl_checked_number {
+    ($($number:ty),*) => {
+        $(
+            impl CheckedNumber for $number {
+                const ZERO: Self = 0;
+                const TEN: Self = 10;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$number>::checked_add(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$number>::checked_mul(self, other)
+                }
+
+                fn from_digit(digit: u8) -> Self {
+                    digit as $number
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_number!(u32, u64, u128, usize);
+
+/// parses `text` as a number, telling a number too large for `T` apart from text that is not a number at all
+pub fn parse_number<T: CheckedNumber>(text: &str, location: Location) -> Result<T, NumberError> {
+    text.parse::<T>().map_err(|err| match err.kind() {
+        IntErrorKind::PosOverflow => NumberError::TooLarge {
+            text: text.to_string(),
+            location,
+        },
+        _ => NumberError::Invalid {
+            text: text.to_string(),
+            location,
+        },
+    })
+}
+
+/// parses every whitespace separated number in `text`
+pub fn parse_numbers<T: CheckedNumber>(
+    text: &str,
+    location: Location,
+) -> Result<Vec<T>, NumberError> {
+    text.split_whitespace()
+        .map(|number| parse_number(number, location.clone()))
+        .collect()
+}
+
+pub fn checked_add<T: CheckedNumber>(a: T, b: T, location: &Location) -> Result<T, NumberError> {
+    a.checked_add(b).ok_or_else(|| NumberError::Overflow {
+        operation: Operation::Adding,
+        location: location.clone(),
+    })
+}
+
+pub fn checked_mul<T: CheckedNumber>(a: T, b: T, location: &Location) -> Result<T, NumberError> {
+    a.checked_mul(b).ok_or_else(|| NumberError::Overflow {
+        operation: Operation::Multiplying,
+        location: location.clone(),
+    })
+}
+
+/// appends one decimal digit to `value`, for building a number up as its digits are read
+pub fn push_digit<T: CheckedNumber>(
+    value: T,
+    digit: u8,
+    location: &Location,
+) -> Result<T, NumberError> {
+    checked_add(
+        checked_mul(value, T::TEN, location)?,
+        T::from_digit(digit),
+        location,
+    )
+}
+
+/// the sum of `values`, naming the location of the value that overflowed
+pub fn checked_sum<T: CheckedNumber>(
+    values: impl IntoIterator<Item = (T, Location)>,
+) -> Result<T, NumberError> {
+    values
+        .into_iter()
+        .try_fold(T::ZERO, |total, (value, location)| {
+            checked_add(total, value, &location)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        checked_add, checked_mul, checked_sum, parse_number, parse_numbers, push_digit, Location,
+        NumberError, Operation,
+    };
+
+    #[test]
+    fn parses_and_tells_errors_apart() {
+        assert_eq!(
+            parse_number::<u32>("4294967295", Location::Line(1)),
+            Ok(u32::MAX)
+        );
+        assert_eq!(
+            parse_number::<u32>("4294967296", Location::Line(2)),
+            Err(NumberError::TooLarge {
+                text: String::from("4294967296"),
+                location: Location::Line(2)
+            })
+        );
+        assert_eq!(
+            parse_number::<usize>("12x", Location::Named(String::from("card 3"))),
+            Err(NumberError::Invalid {
+                text: String::from("12x"),
+                location: Location::Named(String::from("card 3"))
+            })
+        );
+        assert_eq!(
+            parse_numbers::<usize>(" 1  22 333 ", Location::Line(1)),
+            Ok(vec![1, 22, 333])
+        );
+    }
+
+    #[test]
+    fn names_the_location_of_an_overflow() {
+        let location = Location::Cell { line: 3, column: 7 };
+
+        assert_eq!(checked_add(1u32, 2, &location), Ok(3));
+        let err = checked_mul(u64::MAX, 2, &location).unwrap_err();
+        assert_eq!(
+            err,
+            NumberError::Overflow {
+                operation: Operation::Multiplying,
+                location: location.clone()
+            }
+        );
+        assert_eq!(err.to_string(), "Overflow multiplying at line 3, column 7");
+        assert_eq!(
+            push_digit(u32::MAX / 10, 9, &location),
+            Err(NumberError::Overflow {
+                operation: Operation::Adding,
+                location
+            })
+        );
+    }
+
+    #[test]
+    fn sums_with_the_failing_location() {
+        let values = [(usize::MAX - 1, Location::Line(1)), (1, Location::Line(2))];
+        assert_eq!(checked_sum(values), Ok(usize::MAX));
+
+        let values = [(usize::MAX, Location::Line(1)), (1, Location::Line(2))];
+        assert_eq!(
+            checked_sum(values),
+            Err(NumberError::Overflow {
+                operation: Operation::Adding,
+                location: Location::Line(2)
+            })
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use std::{collections::BTreeMap, ops::Range};
+use thiserror::Error;
+
+/// An ordered map from non-overlapping half-open intervals `[start, end)` to values.
+///
+/// Entries are stored in a `BTreeMap` keyed on the interval start, so point lookups,
+/// range queries and inserts stay `O(log n)` no matter what order intervals arrive in.
+#[derive(Debug, Clone)]
+pub struct IntervalMap<K, V> {
+    entries: BTreeMap<K, Entry<K, V>>,
+}
+
+#[derive(Debug, Clone)]
+struct Entry<K, V> {
+    end: K,
+    value: V,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum IntervalMapError {
+    #[error("Interval start must be strictly less than its end")]
+    EmptyInterval,
+
+    #[error("Interval overlaps an existing entry")]
+    Overlap,
+}
+
+impl<K: Ord + Copy, V> IntervalMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// inserts `value` over `interval`, refusing to overwrite any existing entry it overlaps
+    pub fn try_insert(&mut self, interval: Range<K>, value: V) -> Result<(), IntervalMapError> {
+        if interval.start >= interval.end {
+            return Err(IntervalMapError::EmptyInterval);
+        }
+        if self.overlapping(interval.clone()).next().is_some() {
+            return Err(IntervalMapError::Overlap);
+        }
+        self.entries.insert(
+            interval.start,
+            Entry {
+                end: interval.end,
+                value,
+            },
+        );
+        Ok(())
+    }
+
+    /// inserts `value` over `interval`; any existing entries it overlaps are removed and replaced
+    /// by a single entry spanning the union, with values folded together by `merge`
+    pub fn insert_merging<F>(
+        &mut self,
+        interval: Range<K>,
+        value: V,
+        mut merge: F,
+    ) -> Result<(), IntervalMapError>
+    where
+        F: FnMut(V, V) -> V,
+    {
+        if interval.start >= interval.end {
+            return Err(IntervalMapError::EmptyInterval);
+        }
+        let overlapping_keys: Vec<K> = self
+            .overlapping(interval.clone())
+            .map(|(range, _)| range.start)
+            .collect();
+
+        let mut start = interval.start;
+        let mut end = interval.end;
+        let mut merged = value;
+        for key in overlapping_keys {
+            if let Some(entry) = self.entries.remove(&key) {
+                start = start.min(key);
+                end = end.max(entry.end);
+                merged = merge(merged, entry.value);
+            }
+        }
+        self.entries.insert(start, Entry { end, value: merged });
+        Ok(())
+    }
+
+    /// returns the interval containing `point` and its value, if there is one
+    pub fn get(&self, point: K) -> Option<(Range<K>, &V)> {
+        self.entries
+            .range(..=point)
+            .next_back()
+            .filter(|(_, entry)| point < entry.end)
+            .map(|(start, entry)| (*start..entry.end, &entry.value))
+    }
+
+    /// iterates, in order, over every entry that shares at least one point with `interval`
+    pub fn overlapping(&self, interval: Range<K>) -> impl Iterator<Item = (Range<K>, &V)> + '_ {
+        // only the entry starting at or before `interval.start` can reach into it from the left
+        let lower = self
+            .entries
+            .range(..=interval.start)
+            .next_back()
+            .filter(|(_, entry)| interval.start < entry.end)
+            .map(|(start, _)| *start)
+            .unwrap_or(interval.start);
+        let upper = interval.end.max(lower);
+        self.entries
+            .range(lower..upper)
+            .map(|(start, entry)| (*start..entry.end, &entry.value))
+    }
+
+    /// iterates over every entry in ascending order of interval start
+    pub fn iter(&self) -> impl Iterator<Item = (Range<K>, &V)> + '_ {
+        self.entries
+            .iter()
+            .map(|(start, entry)| (*start..entry.end, &entry.value))
+    }
+}
+
+impl<K: Ord + Copy, V> Default for IntervalMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IntervalMap, IntervalMapError};
+
+    mod try_insert {
+        use super::{IntervalMap, IntervalMapError};
+
+        #[test]
+        fn rejects_overlapping_intervals() {
+            let mut map: IntervalMap<usize, char> = IntervalMap::new();
+            map.try_insert(10..20, 'a').unwrap();
+
+            assert_eq!(map.try_insert(15..25, 'b'), Err(IntervalMapError::Overlap));
+            assert_eq!(map.try_insert(0..11, 'b'), Err(IntervalMapError::Overlap));
+            assert_eq!(map.try_insert(12..13, 'b'), Err(IntervalMapError::Overlap));
+            assert!(map.try_insert(20..25, 'b').is_ok());
+            assert!(map.try_insert(0..10, 'c').is_ok());
+        }
+
+        #[test]
+        fn rejects_empty_intervals() {
+            let mut map: IntervalMap<usize, ()> = IntervalMap::new();
+
+            assert_eq!(
+                map.try_insert(5..5, ()),
+                Err(IntervalMapError::EmptyInterval)
+            );
+        }
+
+        #[test]
+        fn sorted_inserts_do_not_degrade() {
+            // the old path-keyed trees overflowed their keys at depth 63 on sorted input
+            let mut map: IntervalMap<usize, usize> = IntervalMap::new();
+            for i in 0..100_000 {
+                map.try_insert(i * 10..i * 10 + 5, i).unwrap();
+            }
+
+            assert_eq!(map.get(999_993), Some((999_990..999_995, &99_999)));
+            assert_eq!(map.get(999_996), None);
+        }
+    }
+
+    mod insert_merging {
+        use super::{IntervalMap, IntervalMapError};
+
+        fn entries(map: &IntervalMap<usize, usize>) -> Vec<(std::ops::Range<usize>, usize)> {
+            map.iter().map(|(range, value)| (range, *value)).collect()
+        }
+
+        #[test]
+        fn merges_a_partial_overlap() {
+            let mut map: IntervalMap<usize, usize> = IntervalMap::new();
+            map.insert_merging(0..10, 1, |a, b| a + b).unwrap();
+            map.insert_merging(5..15, 2, |a, b| a + b).unwrap();
+
+            assert_eq!(entries(&map), vec![(0..15, 3)]);
+        }
+
+        #[test]
+        fn merges_a_full_containment() {
+            let mut map: IntervalMap<usize, usize> = IntervalMap::new();
+            map.insert_merging(0..20, 1, |a, b| a + b).unwrap();
+            map.insert_merging(5..10, 2, |a, b| a + b).unwrap();
+            assert_eq!(entries(&map), vec![(0..20, 3)]);
+
+            // and the other way round, a new interval swallowing a stored one
+            map.insert_merging(30..35, 4, |a, b| a + b).unwrap();
+            map.insert_merging(25..40, 8, |a, b| a + b).unwrap();
+            assert_eq!(entries(&map), vec![(0..20, 3), (25..40, 12)]);
+        }
+
+        #[test]
+        fn keeps_touching_intervals_apart() {
+            let mut map: IntervalMap<usize, usize> = IntervalMap::new();
+            map.insert_merging(0..5, 1, |a, b| a + b).unwrap();
+            map.insert_merging(5..10, 2, |a, b| a + b).unwrap();
+            map.insert_merging(10..15, 4, |a, b| a + b).unwrap();
+
+            assert_eq!(entries(&map), vec![(0..5, 1), (5..10, 2), (10..15, 4)]);
+        }
+
+        #[test]
+        fn bridges_several_stored_intervals() {
+            let mut map: IntervalMap<usize, usize> = IntervalMap::new();
+            map.insert_merging(0..5, 1, |a, b| a + b).unwrap();
+            map.insert_merging(10..15, 2, |a, b| a + b).unwrap();
+            map.insert_merging(20..25, 4, |a, b| a + b).unwrap();
+            map.insert_merging(30..35, 8, |a, b| a + b).unwrap();
+            map.insert_merging(3..22, 16, |a, b| a + b).unwrap();
+
+            assert_eq!(entries(&map), vec![(0..25, 23), (30..35, 8)]);
+        }
+
+        #[test]
+        fn rejects_empty_intervals() {
+            let mut map: IntervalMap<usize, usize> = IntervalMap::new();
+            map.insert_merging(0..5, 1, |a, b| a + b).unwrap();
+
+            assert_eq!(
+                map.insert_merging(3..3, 2, |a, b| a + b),
+                Err(IntervalMapError::EmptyInterval)
+            );
+            assert_eq!(entries(&map), vec![(0..5, 1)]);
+        }
+    }
+
+    #[test]
+    fn overlapping_returns_entries_in_order() {
+        let mut map: IntervalMap<usize, char> = IntervalMap::new();
+        map.try_insert(30..40, 'c').unwrap();
+        map.try_insert(0..10, 'a').unwrap();
+        map.try_insert(15..20, 'b').unwrap();
+
+        let found: Vec<_> = map.overlapping(5..31).map(|(_, value)| *value).collect();
+        assert_eq!(found, vec!['a', 'b', 'c']);
+
+        assert_eq!(map.overlapping(10..15).count(), 0);
+        assert_eq!(map.overlapping(20..30).count(), 0);
+    }
+}
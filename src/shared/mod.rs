@@ -1,3 +1,18 @@
+mod answer;
+mod big_uint;
+mod bit_grid;
+mod checked;
+mod interval_map;
+
+pub use answer::Answer;
+pub use big_uint::BigUint;
+pub use bit_grid::{BitGrid, BitRow};
+pub use checked::{
+    checked_add, checked_mul, checked_sum, parse_number, parse_numbers, push_digit, Location,
+    NumberError,
+};
+pub use interval_map::{IntervalMap, IntervalMapError};
+
 /// Converts a string slice into a vector of characters.
 ///
 /// # Arguments
@@ -33,6 +48,6 @@ pub fn get_chars(input: &str) -> Vec<char> {
 }
 
 pub struct DayResult {
-    pub part_1: usize,
-    pub part_2: usize,
-}
\ No newline at end of file
+    pub part_1: Answer,
+    pub part_2: Answer,
+}